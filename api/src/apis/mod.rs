@@ -1,8 +1,21 @@
 pub mod google_oauth2;
+pub mod normalize;
 pub mod youtube;
+pub mod youtube_quota;
 
-use lazy_static::lazy_static;
+use std::time::Duration;
+use crate::config::Config;
 
-lazy_static! {
-    static ref CLIENT: reqwest::Client = reqwest::Client::new();
+/// Build the HTTP client used for all requests to upstream providers
+pub fn build_client(config: &Config) -> reqwest::Result<reqwest::Client> {
+    let mut builder = reqwest::Client::builder()
+        .timeout(Duration::from_secs(config.http_timeout))
+        .connect_timeout(Duration::from_secs(config.http_connect_timeout))
+        .user_agent(config.http_user_agent.as_str());
+
+    if let Some(proxy) = &config.http_proxy {
+        builder = builder.proxy(reqwest::Proxy::all(proxy.as_str())?);
+    }
+
+    builder.build()
 }
\ No newline at end of file
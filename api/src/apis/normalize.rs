@@ -0,0 +1,249 @@
+/// Words marking a bracketed part of a title as noise, e.g. "(Official Video)" or "[Lyrics]"
+const NOISE_WORDS: &[&str] = &[
+    "official", "video", "videoclip", "clip", "audio", "lyric", "lyrics", "visualizer", "visualiser",
+    "hd", "hq", "4k", "mv", "m/v",
+];
+
+/// Words which may appear alongside noise words in a `|` separated part of a title, e.g. "| Official Music Video"
+const FILLER_WORDS: &[&str] = &["music", "new", "full", "the", "with", "and", "&", "version", "song"];
+
+/// Separators between the artist and the title, in order of preference
+const ARTIST_SEPARATORS: &[&str] = &[" - ", " – ", " — ", " -- ", " ~ "];
+
+/// Suffix of channels auto generated by YouTube for an artist
+const TOPIC_SUFFIX: &str = " - Topic";
+
+/// Suffixes of label run channels, e.g. "Artist VEVO" or "Artist Official", only stripped at a word boundary.
+/// If set, the suffix may also directly follow a lowercase letter, e.g. "ArtistVEVO"
+const CHANNEL_SUFFIXES: &[(&str, bool)] = &[("VEVO", true), ("Official", false)];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrackMetadata {
+    pub name: String,
+    pub artist: String,
+}
+
+/// Normalize the title and channel name of a YouTube video into a track name and artist,
+/// e.g. "Artist - Song (Official Video)" by "ArtistVEVO" becomes "Song" by "Artist"
+pub fn normalize_youtube(title: &str, channel_title: &str) -> TrackMetadata {
+    let channel_title = channel_title.trim();
+    let title = strip_noise(title);
+
+    // Titles of videos on auto generated channels are just the track name,
+    // any dash in them is part of the name, e.g. "Song - Remastered 2011"
+    if let Some(artist) = strip_suffix_ignore_case(channel_title, TOPIC_SUFFIX) {
+        return TrackMetadata {
+            name: title,
+            artist: artist.trim().to_string(),
+        };
+    }
+
+    match split_artist(&title) {
+        Some((artist, name)) => TrackMetadata {
+            name,
+            artist,
+        },
+        None => TrackMetadata {
+            name: title,
+            artist: clean_channel(channel_title),
+        }
+    }
+}
+
+/// Remove label suffixes from a channel name. If nothing remains, the channel name is returned as-is
+fn clean_channel(channel_title: &str) -> String {
+    let mut cleaned = channel_title;
+    while let Some(x) = CHANNEL_SUFFIXES.iter().find_map(|(suffix, camel_case)| strip_channel_suffix(cleaned, suffix, *camel_case)) {
+        cleaned = x.trim_end();
+    }
+
+    if cleaned.is_empty() {
+        channel_title.to_string()
+    } else {
+        cleaned.to_string()
+    }
+}
+
+/// Strip a suffix from a channel name if it is a separate word, so "Unofficial" is left as-is
+fn strip_channel_suffix<'a>(channel: &'a str, suffix: &str, camel_case: bool) -> Option<&'a str> {
+    let rest = strip_suffix_ignore_case(channel, suffix)?;
+    if rest.ends_with(char::is_whitespace) {
+        return Some(rest);
+    }
+
+    // "ArtistVEVO": the suffix is written as-is and the casing changes where it starts
+    let is_camel_case = camel_case
+        && channel.ends_with(suffix)
+        && rest.chars().last().map(char::is_lowercase).unwrap_or(false);
+    is_camel_case.then_some(rest)
+}
+
+/// Split a title in the form "Artist - Title"
+fn split_artist(title: &str) -> Option<(String, String)> {
+    let (artist, name) = ARTIST_SEPARATORS.iter()
+        .find_map(|separator| title.split_once(separator))?;
+
+    let artist = artist.trim();
+    let name = name.trim().trim_matches(&['"', '\'', '“', '”'][..]).trim();
+    if artist.is_empty() || name.is_empty() {
+        return None;
+    }
+
+    Some((artist.to_string(), name.to_string()))
+}
+
+/// Remove bracketed and `|` separated noise from a title, and collapse whitespace
+fn strip_noise(title: &str) -> String {
+    let mut result = String::with_capacity(title.len());
+    let mut rest = title;
+    while let Some(start) = rest.find(&['(', '['][..]) {
+        let close = if rest[start..].starts_with('(') { ')' } else { ']' };
+        let end = match rest[start..].find(close) {
+            Some(x) => start + x,
+            None => break,
+        };
+
+        result.push_str(&rest[..start]);
+        let group = &rest[start..=end];
+        if !is_noise(&group[1..group.len() - 1], false) {
+            result.push_str(group);
+        }
+
+        rest = &rest[end + 1..];
+    }
+    result.push_str(rest);
+
+    let mut segments = result.split('|');
+    let first = segments.next().unwrap_or_default().to_string();
+    let result = segments
+        .filter(|x| !is_noise(x, true))
+        .fold(first, |acc, x| format!("{acc}|{x}"));
+
+    let collapsed = result.split_whitespace().collect::<Vec<_>>().join(" ");
+    let trimmed = collapsed.trim_end_matches(|c: char| c == '-' || c == '|' || c == '–' || c.is_whitespace());
+    if trimmed.is_empty() {
+        title.trim().to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// Whether a part of a title is noise. If `strict` is set, every word must be a noise or filler word,
+/// otherwise a single noise word suffices
+fn is_noise(part: &str, strict: bool) -> bool {
+    let part = part.to_lowercase();
+    let mut words = part
+        .split(|c: char| !(c.is_alphanumeric() || c == '/' || c == '&'))
+        .filter(|x| !x.is_empty())
+        .peekable();
+
+    if words.peek().is_none() {
+        return false;
+    }
+
+    if strict {
+        let mut has_noise = false;
+        for word in words {
+            if NOISE_WORDS.contains(&word) {
+                has_noise = true;
+            } else if !FILLER_WORDS.contains(&word) {
+                return false;
+            }
+        }
+
+        has_noise
+    } else {
+        words.any(|x| NOISE_WORDS.contains(&x))
+    }
+}
+
+fn strip_suffix_ignore_case<'a>(value: &'a str, suffix: &str) -> Option<&'a str> {
+    let idx = value.len().checked_sub(suffix.len())?;
+    if !value.is_char_boundary(idx) || !value[idx..].eq_ignore_ascii_case(suffix) {
+        return None;
+    }
+
+    Some(&value[..idx])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Title, channel title, expected track name, expected artist
+    const FIXTURES: &[(&str, &str, &str, &str)] = &[
+        // Artist - Title patterns with noise suffixes
+        ("Rick Astley - Never Gonna Give You Up (Official Music Video)", "RickAstleyVEVO", "Never Gonna Give You Up", "Rick Astley"),
+        ("Artist - Title (Official Video)", "ArtistVEVO", "Title", "Artist"),
+        ("Daft Punk - Get Lucky (Official Audio) ft. Pharrell Williams, Nile Rodgers", "Daft Punk", "Get Lucky ft. Pharrell Williams, Nile Rodgers", "Daft Punk"),
+        ("Adele - Hello", "AdeleVEVO", "Hello", "Adele"),
+        ("Coldplay - Yellow (Official Video)", "Coldplay", "Yellow", "Coldplay"),
+        ("Imagine Dragons - Believer [Official Video]", "ImagineDragonsVEVO", "Believer", "Imagine Dragons"),
+        ("Billie Eilish - bad guy (Lyrics)", "7clouds", "bad guy", "Billie Eilish"),
+        ("Artist - Title [Lyrics]", "Lyrics Channel", "Title", "Artist"),
+        ("Artist - Title [Lyric Video]", "Some Label", "Title", "Artist"),
+        ("Artist - Title (Official HD Video)", "Artist", "Title", "Artist"),
+        ("Artist - Title (Audio)", "Artist", "Title", "Artist"),
+        ("Artist - Title (Visualizer)", "Artist", "Title", "Artist"),
+        ("Artist - Title [4K]", "Artist", "Title", "Artist"),
+        ("Artist - Title [MV]", "Artist", "Title", "Artist"),
+        ("Title (Clip Officiel)", "Artist", "Title", "Artist"),
+        ("Title (Videoclip)", "Artist", "Title", "Artist"),
+        ("Artist -  Title  (Official Video) ", "Artist", "Title", "Artist"),
+        ("Title (Lyrics) -", "Artist", "Title", "Artist"),
+
+        // `|` separated noise
+        ("Title | Official Music Video", "Artist", "Title", "Artist"),
+        ("Artist - Title | Official Video", "ArtistVEVO", "Title", "Artist"),
+        ("Title | Lyrics", "Artist", "Title", "Artist"),
+        ("Title | Live at Wembley", "Artist", "Title | Live at Wembley", "Artist"),
+
+        // Brackets which are part of the title are kept
+        ("Artist - Title (feat. Other Artist)", "ArtistVEVO", "Title (feat. Other Artist)", "Artist"),
+        ("Artist - Title (feat. Other Artist) (Official Video)", "ArtistVEVO", "Title (feat. Other Artist)", "Artist"),
+        ("Artist - Title (Remix)", "Artist", "Title (Remix)", "Artist"),
+        ("Artist - Title (Acoustic Version)", "Artist", "Title (Acoustic Version)", "Artist"),
+        ("Artist - Title (Live)", "Artist", "Title (Live)", "Artist"),
+        ("Artist - Title [Remastered 2011]", "Artist", "Title [Remastered 2011]", "Artist"),
+        ("Title (Official Video", "Artist", "Title (Official Video", "Artist"),
+        ("(Official Video)", "Artist", "(Official Video)", "Artist"),
+
+        // Separators and quotes
+        ("Artist - \"Title\"", "Artist", "Title", "Artist"),
+        ("Artist – Title", "Artist", "Title", "Artist"),
+        ("Artist — Title (Official Video)", "Artist", "Title", "Artist"),
+        ("Artist ~ Title", "Artist", "Title", "Artist"),
+
+        // Dash titles from channels which are not Topic channels are split
+        ("Some Show - Episode 4", "Some Channel", "Episode 4", "Some Show"),
+        ("Live Session - Full Concert", "Venue Channel", "Full Concert", "Live Session"),
+
+        // Topic channels
+        ("Title", "Artist - Topic", "Title", "Artist"),
+        ("Title - Remastered 2011", "Artist - Topic", "Title - Remastered 2011", "Artist"),
+        ("Title (feat. Other Artist)", "Artist - Topic", "Title (feat. Other Artist)", "Artist"),
+        ("Title", "Artist - topic", "Title", "Artist"),
+
+        // Channel names
+        ("Title", "ArtistVEVO", "Title", "Artist"),
+        ("Title", "Artist VEVO", "Title", "Artist"),
+        ("Title", "Artist Official", "Title", "Artist"),
+        ("Title", "Artist official", "Title", "Artist"),
+        ("Title", "Unofficial", "Title", "Unofficial"),
+        ("Title", "The Unofficial Band", "Title", "The Unofficial Band"),
+        ("Title", "Devevo", "Title", "Devevo"),
+        ("Title", "VEVO", "Title", "VEVO"),
+        ("Title", "Official", "Title", "Official"),
+        ("Title", "  Artist  ", "Title", "Artist"),
+    ];
+
+    #[test]
+    fn fixtures() {
+        for (title, channel_title, name, artist) in FIXTURES {
+            assert_eq!(normalize_youtube(title, channel_title), TrackMetadata {
+                name: name.to_string(),
+                artist: artist.to_string(),
+            }, "{title} by {channel_title}");
+        }
+    }
+}
\ No newline at end of file
@@ -1,38 +1,128 @@
 use std::collections::HashMap;
 use std::fmt::Debug;
-use reqwest::Result;
-use crate::apis::CLIENT;
+use std::sync::Arc;
+use reqwest::{RequestBuilder, Response, StatusCode};
 use serde::{Serialize, Deserialize};
+use thiserror::Error;
 use tracing::instrument;
+use crate::apis::normalize::{normalize_youtube, TrackMetadata};
+use crate::apis::youtube_quota::{QuotaTracker, YouTubeCall};
+
+pub type Result<T> = std::result::Result<T, YouTubeError>;
+
+#[derive(Debug, Error)]
+pub enum YouTubeError {
+    #[error("Request failed: {0}")]
+    Reqwest(#[from] reqwest::Error),
+    #[error("Video has no thumbnails")]
+    NoThumbnail,
+    #[error("Invalid ISO-8601 duration '{0}'")]
+    InvalidDuration(String),
+    #[error("YouTube API quota exceeded, retry after {retry_after} seconds")]
+    QuotaExceeded {
+        retry_after: u64,
+    },
+    #[error("Request forbidden: {0}")]
+    Forbidden(String),
+}
+
+/// Reasons a video can not be played by the player
+#[derive(Debug, Clone, Error)]
+pub enum Unplayable {
+    #[error("The video is a live stream or premiere")]
+    LiveStream,
+    #[error("The video may not be embedded")]
+    NotEmbeddable,
+    #[error("The video is private")]
+    Private,
+    #[error("The video has not finished processing")]
+    NotProcessed,
+    #[error("The video is not available in region {0}")]
+    RegionBlocked(String),
+}
+
+/// Error reasons returned by the Data API when the quota is exhausted
+const QUOTA_EXCEEDED_REASONS: [&str; 2] = ["quotaExceeded", "dailyLimitExceeded"];
 
 #[derive(Debug, Clone)]
 pub struct YouTubeApi {
-    pub token: String
+    pub token: String,
+    /// Base URL of the Data API, e.g. `https://www.googleapis.com/youtube/v3`
+    pub base_url: String,
+    pub client: reqwest::Client,
+    pub quota: Arc<QuotaTracker>,
 }
 
-#[derive(Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct VideoResource {
     pub id: String,
     pub snippet: VideoResourceSnippet,
     pub content_details: VideoResourceContentDetails,
+    /// Absent in metadata cached before the status was requested
+    #[serde(default)]
+    pub status: Option<VideoResourceStatus>,
 }
 
-#[derive(Deserialize)]
+impl VideoResource {
+    /// Check whether the video can be played, optionally in a specific ISO 3166-1 alpha-2 region
+    pub fn check_playable(&self, region_code: Option<&str>) -> std::result::Result<(), Unplayable> {
+        match self.snippet.live_broadcast_content.as_deref() {
+            Some("live") | Some("upcoming") => return Err(Unplayable::LiveStream),
+            _ => {}
+        }
+
+        if let Some(status) = &self.status {
+            if status.privacy_status.eq("private") {
+                return Err(Unplayable::Private);
+            }
+
+            if !status.embeddable {
+                return Err(Unplayable::NotEmbeddable);
+            }
+
+            if status.upload_status.ne("processed") {
+                return Err(Unplayable::NotProcessed);
+            }
+        }
+
+        if let (Some(region_code), Some(restriction)) = (region_code, &self.content_details.region_restriction) {
+            if !restriction.allows(region_code) {
+                return Err(Unplayable::RegionBlocked(region_code.to_string()));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VideoResourceStatus {
+    pub upload_status: String,
+    pub privacy_status: String,
+    pub embeddable: bool,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct VideoResourceSnippet {
-    title: String,
+    pub title: String,
     pub thumbnails: HashMap<String, VideoThumbnail>,
     pub channel_title: String,
+    /// `none`, `live` or `upcoming`
+    #[serde(default)]
+    pub live_broadcast_content: Option<String>,
 }
 
 impl VideoResourceSnippet {
-    pub fn get_title(&self) -> String {
-        self.title.replace("- Topic", "")
+    /// Get the track name and artist from the video title and channel
+    pub fn get_metadata(&self) -> TrackMetadata {
+        normalize_youtube(&self.title, &self.channel_title)
     }
 
-    pub fn get_best_thumbnail(&self) -> String {
-        if let Some(x) = self.thumbnails.get("maxres") {
+    pub fn get_best_thumbnail(&self) -> Result<String> {
+        let url = if let Some(x) = self.thumbnails.get("maxres") {
             &x.url
         } else if let Some(x) = self.thumbnails.get("standard") {
             &x.url
@@ -43,25 +133,53 @@ impl VideoResourceSnippet {
         } else if let Some(x) = self.thumbnails.get("default") {
             &x.url
         } else {
-            panic!("No thumbnail found");
-        }.to_string()
+            return Err(YouTubeError::NoThumbnail);
+        };
+
+        Ok(url.to_string())
     }
 }
 
-#[derive(Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct VideoThumbnail {
     pub url: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct VideoResourceContentDetails {
     pub duration: String,
+    #[serde(default)]
+    pub region_restriction: Option<VideoRegionRestriction>,
+}
+
+/// Either a list of regions the video is allowed in, or a list of regions it is blocked in
+#[derive(Clone, Serialize, Deserialize)]
+pub struct VideoRegionRestriction {
+    #[serde(default)]
+    pub allowed: Option<Vec<String>>,
+    #[serde(default)]
+    pub blocked: Option<Vec<String>>,
+}
+
+impl VideoRegionRestriction {
+    pub fn allows(&self, region_code: &str) -> bool {
+        if let Some(allowed) = &self.allowed {
+            return allowed.iter().any(|x| x.eq_ignore_ascii_case(region_code));
+        }
+
+        match &self.blocked {
+            Some(blocked) => !blocked.iter().any(|x| x.eq_ignore_ascii_case(region_code)),
+            None => true,
+        }
+    }
 }
 
 impl VideoResourceContentDetails {
-    pub fn duration(&self) -> i64 {
-        let dur = iso8601_duration::Duration::parse(&self.duration).expect("Parsing duration");
-        dur.to_std().as_secs() as i64
+    pub fn duration(&self) -> Result<i64> {
+        let dur = iso8601_duration::Duration::parse(&self.duration)
+            .map_err(|_| YouTubeError::InvalidDuration(self.duration.clone()))?;
+        Ok(dur.to_std().as_secs() as i64)
     }
 }
 
@@ -80,6 +198,58 @@ struct SearchQuery {
     key: String,
     max_results: u32,
     topic_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    page_token: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PlaylistItemsQuery {
+    part: String,
+    playlist_id: String,
+    key: String,
+    max_results: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    page_token: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaylistItemResource {
+    pub content_details: PlaylistItemContentDetails,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaylistItemContentDetails {
+    pub video_id: String,
+}
+
+/// The maximum value for `maxResults` and the maximum amount of IDs per request the Data API accepts
+const MAX_PAGE_SIZE: usize = 50;
+
+#[derive(Deserialize)]
+struct ErrorResponse {
+    error: ErrorResponseError,
+}
+
+#[derive(Deserialize)]
+struct ErrorResponseError {
+    message: String,
+    #[serde(default)]
+    errors: Vec<ErrorResponseReason>,
+}
+
+#[derive(Deserialize)]
+struct ErrorResponseReason {
+    reason: String,
+}
+
+/// A page of search results
+pub struct SearchPage {
+    pub items: Vec<SearchResource>,
+    /// Token to retrieve the next page, absent on the last page
+    pub next_page_token: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -94,21 +264,59 @@ pub struct SearchResourceId {
 }
 
 impl YouTubeApi {
+    fn endpoint(&self, resource: &str) -> String {
+        format!("{}/{resource}", self.base_url.trim_end_matches('/'))
+    }
+
+    /// Send a request to the Data API, keeping track of the quota consumed.
+    /// No request is sent while the quota is known to be exhausted
+    async fn send(&self, call: YouTubeCall, request: RequestBuilder) -> Result<Response> {
+        if let Some(retry_after) = self.quota.exhausted_for() {
+            return Err(YouTubeError::QuotaExceeded { retry_after });
+        }
+
+        let response = request.send().await?;
+        if response.status() == StatusCode::FORBIDDEN {
+            let status = response.status();
+            // A proxy in between may respond with something other than the Data API's error format
+            let body: ErrorResponse = match serde_json::from_str(&response.text().await?) {
+                Ok(x) => x,
+                Err(_) => {
+                    self.quota.record(call);
+                    return Err(YouTubeError::Forbidden(format!("YouTube responded with {status}")));
+                }
+            };
+
+            if body.error.errors.iter().any(|x| QUOTA_EXCEEDED_REASONS.contains(&x.reason.as_str())) {
+                self.quota.mark_exhausted();
+                return Err(YouTubeError::QuotaExceeded {
+                    retry_after: self.quota.exhausted_for().unwrap_or_default()
+                });
+            }
+
+            self.quota.record(call);
+            return Err(YouTubeError::Forbidden(body.error.message));
+        }
+
+        self.quota.record(call);
+        Ok(response)
+    }
+
     #[instrument]
     pub async fn get_video<S: AsRef<str> + Debug>(&self, video_id: S) -> Result<Option<VideoResource>> {
         #[derive(Deserialize)]
-        struct Response {
+        struct VideosResponse {
             items: Vec<VideoResource>
         }
 
-        let response: Response = CLIENT.get(" https://www.googleapis.com/youtube/v3/videos")
+        let request = self.client.get(self.endpoint("videos"))
             .query(&GetVideoQuery {
-                part: "snippet,contentDetails".into(),
+                part: "snippet,contentDetails,status".into(),
                 id: video_id.as_ref().to_string(),
                 key: self.token.clone()
-            })
-            .send()
-            .await?
+            });
+        let response: VideosResponse = self.send(YouTubeCall::Videos, request).await?
+            .error_for_status()?
             .json()
             .await?;
         if let Some(v) = response.items.into_iter().nth(0) {
@@ -118,26 +326,197 @@ impl YouTubeApi {
         }
     }
 
+    /// Get multiple videos at once. Videos which do not exist are omitted from the result
+    #[instrument(skip(video_ids))]
+    pub async fn get_videos(&self, video_ids: &[String]) -> Result<Vec<VideoResource>> {
+        #[derive(Deserialize)]
+        struct VideosResponse {
+            items: Vec<VideoResource>
+        }
+
+        let mut videos = Vec::with_capacity(video_ids.len());
+        for chunk in video_ids.chunks(MAX_PAGE_SIZE) {
+            let request = self.client.get(self.endpoint("videos"))
+                .query(&GetVideoQuery {
+                    part: "snippet,contentDetails,status".into(),
+                    id: chunk.join(","),
+                    key: self.token.clone()
+                });
+            let response: VideosResponse = self.send(YouTubeCall::Videos, request).await?
+                .error_for_status()?
+                .json()
+                .await?;
+            videos.extend(response.items);
+        }
+
+        Ok(videos)
+    }
+
+    /// Get the IDs of the videos in a playlist, in playlist order, up to `limit` videos.
+    /// Returns `None` if the playlist does not exist
     #[instrument]
-    pub async fn search<S: AsRef<str> + Debug>(&self, q: S) -> Result<Vec<SearchResource>> {
+    pub async fn get_playlist_video_ids<S: AsRef<str> + Debug>(&self, playlist_id: S, limit: usize) -> Result<Option<Vec<String>>> {
         #[derive(Deserialize)]
-        struct Response {
-            items: Vec<SearchResource>
+        #[serde(rename_all = "camelCase")]
+        struct PlaylistItemsResponse {
+            items: Vec<PlaylistItemResource>,
+            next_page_token: Option<String>,
+        }
+
+        let mut video_ids = Vec::new();
+        let mut page_token = None;
+        loop {
+            let request = self.client.get(self.endpoint("playlistItems"))
+                .query(&PlaylistItemsQuery {
+                    part: "contentDetails".into(),
+                    playlist_id: playlist_id.as_ref().to_string(),
+                    key: self.token.clone(),
+                    max_results: MAX_PAGE_SIZE as u32,
+                    page_token,
+                });
+            let response = self.send(YouTubeCall::PlaylistItems, request).await?;
+
+            if response.status() == StatusCode::NOT_FOUND {
+                return Ok(None);
+            }
+
+            let response: PlaylistItemsResponse = response
+                .error_for_status()?
+                .json()
+                .await?;
+
+            video_ids.extend(response.items.into_iter().map(|x| x.content_details.video_id));
+            if video_ids.len() >= limit {
+                video_ids.truncate(limit);
+                break;
+            }
+
+            page_token = match response.next_page_token {
+                Some(x) => Some(x),
+                None => break,
+            };
         }
 
-        let response: Response = CLIENT.get("https://www.googleapis.com/youtube/v3/search")
+        Ok(Some(video_ids))
+    }
+
+    #[instrument]
+    pub async fn search<S: AsRef<str> + Debug>(&self, q: S, max_results: u32, page_token: Option<&str>) -> Result<SearchPage> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct SearchResponse {
+            items: Vec<SearchResource>,
+            next_page_token: Option<String>,
+        }
+
+        let request = self.client.get(self.endpoint("search"))
             .query(&SearchQuery {
                 q: q.as_ref().to_string(),
                 part: "snippet".into(),
                 key: self.token.clone(),
-                max_results: 5,
+                max_results,
                 topic_id: "/m/04rlf".into(),
-            })
-            .send()
-            .await?
+                page_token: page_token.map(str::to_string),
+            });
+        let response: SearchResponse = self.send(YouTubeCall::Search, request).await?
+            .error_for_status()?
             .json()
             .await?;
 
-        Ok(response.items)
+        Ok(SearchPage {
+            items: response.items,
+            next_page_token: response.next_page_token,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use actix_web::ResponseError;
+    use actix_web::http::StatusCode as HttpStatusCode;
+    use crate::error::Error;
+    use super::*;
+
+    const VIDEOS_NO_THUMBNAILS: &str = include_str!("../../tests/fixtures/youtube/videos_no_thumbnails.json");
+    const VIDEOS_INVALID_DURATION: &str = include_str!("../../tests/fixtures/youtube/videos_invalid_duration.json");
+    const ERROR_QUOTA_EXCEEDED: &str = include_str!("../../tests/fixtures/youtube/error_quota_exceeded.json");
+
+    /// Serve a recorded response body to every request. Returns the base URL of the server
+    fn mock_server(body: &'static str) -> String {
+        mock_server_with_status("200 OK", "application/json", body)
+    }
+
+    fn mock_server_with_status(status: &'static str, content_type: &'static str, body: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                // Read the request up to the empty line ending the headers
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut line = String::new();
+                while reader.read_line(&mut line).map(|n| n > 2).unwrap_or(false) {
+                    line.clear();
+                }
+
+                let _ = write!(stream, "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}", body.len());
+            }
+        });
+
+        format!("http://{addr}/youtube/v3")
+    }
+
+    fn api(base_url: String) -> YouTubeApi {
+        YouTubeApi {
+            token: "key".to_string(),
+            base_url,
+            client: reqwest::Client::new(),
+            quota: Arc::new(QuotaTracker::new(10_000)),
+        }
+    }
+
+    #[tokio::test]
+    async fn no_thumbnails() {
+        let video = api(mock_server(VIDEOS_NO_THUMBNAILS)).get_video("dQw4w9WgXcQ").await
+            .unwrap()
+            .unwrap();
+
+        let err = video.snippet.get_best_thumbnail().unwrap_err();
+        assert!(matches!(err, YouTubeError::NoThumbnail));
+        assert_eq!(Error::from(err).status_code(), HttpStatusCode::BAD_GATEWAY);
+
+        // The rest of the response is still usable
+        assert_eq!(video.content_details.duration().unwrap(), 213);
+    }
+
+    #[tokio::test]
+    async fn invalid_duration() {
+        let video = api(mock_server(VIDEOS_INVALID_DURATION)).get_video("dQw4w9WgXcQ").await
+            .unwrap()
+            .unwrap();
+
+        let err = video.content_details.duration().unwrap_err();
+        assert!(matches!(&err, YouTubeError::InvalidDuration(x) if x.eq("3:33")));
+        assert_eq!(Error::from(err).status_code(), HttpStatusCode::BAD_GATEWAY);
+
+        assert!(video.snippet.get_best_thumbnail().is_ok());
+    }
+
+    #[tokio::test]
+    async fn forbidden_quota_exceeded() {
+        let api = api(mock_server_with_status("403 Forbidden", "application/json", ERROR_QUOTA_EXCEEDED));
+        let err = api.get_video("dQw4w9WgXcQ").await.err().unwrap();
+        assert!(matches!(err, YouTubeError::QuotaExceeded { .. }));
+        assert!(api.quota.exhausted_for().is_some());
+    }
+
+    #[tokio::test]
+    async fn forbidden_not_json() {
+        let api = api(mock_server_with_status("403 Forbidden", "text/html", "<html><body>Forbidden</body></html>"));
+        let err = api.get_video("dQw4w9WgXcQ").await.err().unwrap();
+        assert!(matches!(&err, YouTubeError::Forbidden(x) if x.contains("403")));
+        assert_eq!(Error::from(err).status_code(), HttpStatusCode::BAD_GATEWAY);
+        assert!(api.quota.exhausted_for().is_none());
     }
 }
\ No newline at end of file
@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+use parking_lot::Mutex;
+use tracing::warn;
+
+/// The Data API quota resets at midnight Pacific Time.
+/// Daylight saving time is not taken into account, so the reset may be estimated an hour late
+const QUOTA_RESET_UTC_OFFSET: i64 = -8 * 3600;
+const SECONDS_PER_DAY: i64 = 86400;
+
+/// The kinds of Data API calls made, each with their own quota cost
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum YouTubeCall {
+    Videos,
+    Search,
+    PlaylistItems,
+}
+
+impl YouTubeCall {
+    /// The amount of quota units a call costs
+    pub fn cost(&self) -> u64 {
+        match self {
+            Self::Videos => 1,
+            Self::Search => 100,
+            Self::PlaylistItems => 1,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Videos => "videos.list",
+            Self::Search => "search.list",
+            Self::PlaylistItems => "playlistItems.list",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CallUsage {
+    pub calls: u64,
+    pub units: u64,
+}
+
+/// Estimate of the quota consumed during the current quota day
+#[derive(Debug, Clone)]
+pub struct QuotaStatus {
+    pub usage: HashMap<YouTubeCall, CallUsage>,
+    pub used: u64,
+    pub daily_quota: u64,
+    /// Whether the Data API reported the quota as exceeded
+    pub exhausted: bool,
+    /// UNIX timestamp at which the quota resets
+    pub resets_at: i64,
+}
+
+impl QuotaStatus {
+    pub fn remaining(&self) -> u64 {
+        if self.exhausted {
+            0
+        } else {
+            self.daily_quota.saturating_sub(self.used)
+        }
+    }
+}
+
+#[derive(Debug)]
+struct QuotaDay {
+    day: i64,
+    usage: HashMap<YouTubeCall, CallUsage>,
+    exhausted: bool,
+}
+
+/// Tracks the quota consumed by the YouTube Data API key
+#[derive(Debug)]
+pub struct QuotaTracker {
+    daily_quota: u64,
+    state: Mutex<QuotaDay>,
+}
+
+impl QuotaTracker {
+    pub fn new(daily_quota: u64) -> Self {
+        Self {
+            daily_quota,
+            state: Mutex::new(QuotaDay {
+                day: current_day(),
+                usage: HashMap::new(),
+                exhausted: false,
+            }),
+        }
+    }
+
+    /// Get the state for the current quota day, resetting it if the quota has reset since
+    fn with_state<T, F: FnOnce(&mut QuotaDay) -> T>(&self, f: F) -> T {
+        let mut state = self.state.lock();
+        let day = current_day();
+        if state.day != day {
+            *state = QuotaDay {
+                day,
+                usage: HashMap::new(),
+                exhausted: false,
+            };
+        }
+
+        f(&mut state)
+    }
+
+    pub fn record(&self, call: YouTubeCall) {
+        self.with_state(|state| {
+            let usage = state.usage.entry(call).or_default();
+            usage.calls += 1;
+            usage.units += call.cost();
+        });
+    }
+
+    /// Mark the quota as exhausted until it resets
+    pub fn mark_exhausted(&self) {
+        warn!("YouTube Data API quota exceeded, backing off until {}", reset_at());
+        self.with_state(|state| state.exhausted = true);
+    }
+
+    /// If the quota is exhausted, returns the amount of seconds until it resets
+    pub fn exhausted_for(&self) -> Option<u64> {
+        self.with_state(|state| state.exhausted)
+            .then(|| (reset_at() - time::OffsetDateTime::now_utc().unix_timestamp()).max(0) as u64)
+    }
+
+    pub fn status(&self) -> QuotaStatus {
+        self.with_state(|state| QuotaStatus {
+            usage: state.usage.clone(),
+            used: state.usage.values().map(|x| x.units).sum(),
+            daily_quota: self.daily_quota,
+            exhausted: state.exhausted,
+            resets_at: reset_at(),
+        })
+    }
+}
+
+fn current_day() -> i64 {
+    (time::OffsetDateTime::now_utc().unix_timestamp() + QUOTA_RESET_UTC_OFFSET).div_euclid(SECONDS_PER_DAY)
+}
+
+/// UNIX timestamp at which the current quota day ends
+fn reset_at() -> i64 {
+    (current_day() + 1) * SECONDS_PER_DAY - QUOTA_RESET_UTC_OFFSET
+}
@@ -5,6 +5,11 @@ use dal::Mysql;
 use crate::config::Config;
 use thiserror::Error;
 use dal::uuid::Uuid;
+use crate::apis::build_client;
+use crate::apis::youtube::YouTubeApi;
+use crate::apis::youtube_quota::QuotaTracker;
+use crate::cache::YouTubeMetadataCache;
+use crate::push::WebPush;
 use crate::services::sse::broadcaster::AMBroadcaster;
 
 pub type WebData = actix_web::web::Data<Arc<AppData>>;
@@ -14,24 +19,55 @@ pub struct AppData {
     pub config: Config,
     pub dal: Mysql,
     pub sse_track_list: Arc<Mutex<HashMap<Uuid, AMBroadcaster>>>,
+    pub sse_user: Arc<Mutex<HashMap<Uuid, AMBroadcaster>>>,
+    pub youtube_cache: YouTubeMetadataCache,
+    pub http_client: reqwest::Client,
+    pub youtube_quota: Arc<QuotaTracker>,
+    pub web_push: Option<WebPush>,
+    /// Held while autoplay fills the queue of a Room, keyed by Room UUID
+    pub auto_dj_locks: Mutex<HashMap<Uuid, Arc<tokio::sync::Mutex<()>>>>,
+    /// Held while skip votes of a Room are counted, keyed by Room UUID
+    pub skip_vote_locks: Mutex<HashMap<Uuid, Arc<tokio::sync::Mutex<()>>>>,
 }
 
 #[derive(Debug, Error)]
 pub enum AppDataError {
     #[error("Dal error: {0}")]
-    Dal(#[from] dal::Error)
+    Dal(#[from] dal::Error),
+    #[error("Reqwest error: {0}")]
+    Reqwest(#[from] reqwest::Error),
 }
 
 impl AppData {
     pub fn new(config: Config) -> Result<Self, AppDataError> {
         let dal = Mysql::new(&config.mysql_host, &config.mysql_database, &config.mysql_username, &config.mysql_password)?;
+        let youtube_cache = YouTubeMetadataCache::new(&config, &dal);
+        let http_client = build_client(&config)?;
+        let youtube_quota = Arc::new(QuotaTracker::new(config.youtube_daily_quota));
+        let web_push = WebPush::new(&config, dal.clone(), http_client.clone());
         Ok(Self {
             config,
             dal,
             sse_track_list: Arc::new(Mutex::new(HashMap::new())),
+            sse_user: Arc::new(Mutex::new(HashMap::new())),
+            youtube_cache,
+            http_client,
+            youtube_quota,
+            web_push,
+            auto_dj_locks: Mutex::new(HashMap::new()),
+            skip_vote_locks: Mutex::new(HashMap::new()),
         })
     }
 
+    pub fn youtube_api(&self) -> YouTubeApi {
+        YouTubeApi {
+            token: self.config.google_api_key.clone(),
+            base_url: self.config.youtube_api_base_url.clone(),
+            client: self.http_client.clone(),
+            quota: self.youtube_quota.clone(),
+        }
+    }
+
     pub fn migrate(&self) -> Result<(), AppDataError> {
         self.dal.migrate()?;
         Ok(())
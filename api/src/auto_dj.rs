@@ -0,0 +1,125 @@
+use std::collections::HashSet;
+use dal::{Dal, Mysql, Room, RoomExt, Track, TrackBuildable, SYSTEM_USER_UUID};
+use proto::SsePacketEvent;
+use tracing::{debug, warn};
+use crate::appdata::AppData;
+use crate::blocklist::Blocklist;
+use crate::error::WebResult;
+use crate::services::tracks::find::youtube::create_pretrack_from_video;
+use crate::services::tracks::list::{track_list_response, track_to_proto};
+
+/// The amount of played Tracks considered when picking Tracks
+const HISTORY_LIMIT: usize = 50;
+/// The amount of search results requested per seed
+const SEARCH_LIMIT: u32 = 25;
+
+/// Fill the queue of a Room if it is empty.
+/// Tracks related to the most recently played Track are looked up with YouTube.
+/// If none can be found, the Room's least recently played Tracks are enqueued again.
+/// Returns the Tracks enqueued
+pub async fn fill(appdata: &AppData, room: &Room<Mysql>) -> WebResult<Vec<proto::Track>> {
+    // The periodic task and advancing playback may both find the queue empty.
+    // Fills of a Room are serialized, so the queue is checked again once the previous fill is done
+    let lock = appdata.auto_dj_locks.lock()
+        .entry(room.uuid)
+        .or_default()
+        .clone();
+    let _guard = lock.lock().await;
+
+    let queue = room.get_queue()?;
+    if !queue.get_enqueued()?.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let history = room.list_history(HISTORY_LIMIT)?;
+    let seed = match history.first() {
+        Some(x) => x,
+        None => {
+            debug!("Room {} has no history to seed autoplay with", room.uuid);
+            return Ok(Vec::new());
+        }
+    };
+
+    let batch_size = appdata.config.auto_dj_batch_size;
+    let related = related_tracks(appdata, room, seed, &history, batch_size).await;
+    let tracks = match related {
+        Ok(x) if !x.is_empty() => x,
+        Ok(_) => history_tracks(appdata, room, history, batch_size)?,
+        Err(e) => {
+            warn!("Failed to find related tracks for Room {}, falling back to its history: {e}", room.uuid);
+            history_tracks(appdata, room, history, batch_size)?
+        }
+    };
+
+    let tracks = tracks.into_iter()
+        .map(|track| {
+            queue.enqueue(&track, &SYSTEM_USER_UUID)?;
+            track_to_proto(&appdata.dal, &track)
+        })
+        .collect::<WebResult<Vec<_>>>()?;
+
+    if !tracks.is_empty() {
+        debug!("Autoplay enqueued {} track(s) in Room {}", tracks.len(), room.uuid);
+
+        let batch = track_list_response(room, tracks.clone())?;
+        let sse = appdata.sse_track_list.lock();
+        if let Some(broadcaster) = sse.get(&room.uuid) {
+            let lock = broadcaster.lock();
+            lock.send(SsePacketEvent::TrackBatch, batch)?;
+        }
+    }
+
+    Ok(tracks)
+}
+
+/// Search YouTube for Tracks by the artist of the seed, skipping Tracks the Room has already played
+async fn related_tracks(appdata: &AppData, room: &Room<Mysql>, seed: &Track<Mysql>, history: &[Track<Mysql>], limit: usize) -> WebResult<Vec<Track<Mysql>>> {
+    let played = history.iter()
+        .map(|x| x.platform.get_track_id())
+        .collect::<HashSet<_>>();
+
+    let api = appdata.youtube_api();
+    let results = appdata.youtube_cache.search(&api, &seed.artist, SEARCH_LIMIT, None).await?;
+
+    let mut tracks = Vec::new();
+    for video_id in results.video_ids.into_iter().filter(|x| !played.contains(x)) {
+        if tracks.len() >= limit {
+            break;
+        }
+
+        let video = match appdata.youtube_cache.get_video(&api, &video_id).await? {
+            Some(x) => x,
+            None => continue,
+        };
+
+        let pretrack = match create_pretrack_from_video(appdata, video, room, SYSTEM_USER_UUID) {
+            Ok(x) => x,
+            Err(e) => {
+                debug!("Skipping YouTube video {video_id} for autoplay: {e}");
+                continue;
+            }
+        };
+
+        tracks.push(Track::create(appdata.dal.clone(), TrackBuildable {
+            room_uuid: room.uuid,
+            pretrack
+        })?);
+    }
+
+    Ok(tracks)
+}
+
+/// Pick the least recently played Tracks from the history, leaving out Tracks which have since been blocked.
+/// The most recently played Track is only picked if it is the only one
+fn history_tracks(appdata: &AppData, room: &Room<Mysql>, history: Vec<Track<Mysql>>, limit: usize) -> WebResult<Vec<Track<Mysql>>> {
+    let blocklist = Blocklist::for_room(&appdata.dal, room.uuid)?;
+    let skip = if history.len() > 1 { 1 } else { 0 };
+    let tracks = history.into_iter()
+        .skip(skip)
+        .rev()
+        .filter(|x| blocklist.check(&x.platform.get_track_id(), &x.artist, &x.name).is_ok())
+        .take(limit)
+        .collect();
+
+    Ok(tracks)
+}
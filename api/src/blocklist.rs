@@ -0,0 +1,90 @@
+use dal::{BlocklistEntry, BlocklistEntryExt, BlocklistKind, Dal, Mysql, Room};
+use dal::uuid::Uuid;
+use crate::error::{Error, WebResult};
+use crate::services::admin::Admin;
+
+/// The blocklist entries which apply to a Room, or only the global entries
+pub struct Blocklist {
+    entries: Vec<BlocklistEntry<Mysql>>,
+}
+
+impl Blocklist {
+    pub fn for_room(dal: &Mysql, room_uuid: Uuid) -> WebResult<Self> {
+        Ok(Self {
+            entries: BlocklistEntry::list_applicable(dal.clone(), room_uuid)?,
+        })
+    }
+
+    pub fn global(dal: &Mysql) -> WebResult<Self> {
+        Ok(Self {
+            entries: BlocklistEntry::list(dal.clone(), None)?,
+        })
+    }
+
+    /// Check a track against the blocklist. Artists are matched in full and keywords anywhere in the name,
+    /// both ignoring case. Returns `Forbidden` with the reason if the track is blocked
+    pub fn check(&self, platform_id: &str, artist: &str, name: &str) -> WebResult<()> {
+        let artist = artist.trim().to_lowercase();
+        let name = name.to_lowercase();
+
+        let blocked_by = self.entries.iter().find(|x| match x.kind {
+            BlocklistKind::PlatformId => x.value.eq(platform_id),
+            BlocklistKind::Artist => x.value.trim().to_lowercase().eq(&artist),
+            BlocklistKind::Keyword => name.contains(&x.value.to_lowercase()),
+        });
+
+        match blocked_by {
+            None => Ok(()),
+            Some(entry) => Err(Error::Forbidden(match (entry.kind, entry.room_uuid.is_some()) {
+                (BlocklistKind::PlatformId, true) => "This track is blocked in this Room",
+                (BlocklistKind::PlatformId, false) => "This track is blocked",
+                (BlocklistKind::Artist, true) => "Tracks by this artist are blocked in this Room",
+                (BlocklistKind::Artist, false) => "Tracks by this artist are blocked",
+                (BlocklistKind::Keyword, true) => "This track contains a keyword which is blocked in this Room",
+                (BlocklistKind::Keyword, false) => "This track contains a blocked keyword",
+            }))
+        }
+    }
+}
+
+/// Check that the requester may maintain a blocklist. Room blocklists are maintained by the owner of the Room,
+/// the global blocklist by admins. Returns the Room of the blocklist, or `None` for the global blocklist
+pub fn authorize(dal: &Mysql, admin: Admin, room_uuid: Option<&str>, user_uuid: Option<&str>) -> WebResult<Option<Uuid>> {
+    let room_uuid = match room_uuid {
+        Some(x) => Uuid::parse_str(x)?,
+        None if *admin => return Ok(None),
+        None => return Err(Error::Unauthorized("The global blocklist requires the admin token")),
+    };
+
+    let room = match Room::get(dal.clone(), room_uuid)? {
+        Some(x) => x,
+        None => return Err(Error::NotFound("The requested Room does not exist"))
+    };
+
+    let user_uuid = match user_uuid {
+        Some(x) => Uuid::parse_str(x)?,
+        None => return Err(Error::BadRequest("A user UUID is required for the blocklist of a Room")),
+    };
+
+    if room.owner.ne(&user_uuid) {
+        return Err(Error::Forbidden("Only the owner of the Room may maintain its blocklist"));
+    }
+
+    Ok(Some(room.uuid))
+}
+
+pub fn to_proto(entry: &BlocklistEntry<Mysql>) -> proto::BlocklistEntry {
+    let kind = match entry.kind {
+        BlocklistKind::PlatformId => proto::BlocklistKind::PlatformId,
+        BlocklistKind::Artist => proto::BlocklistKind::Artist,
+        BlocklistKind::Keyword => proto::BlocklistKind::Keyword,
+    };
+
+    proto::BlocklistEntry {
+        entry_uuid: entry.uuid.to_string(),
+        room_uuid: entry.room_uuid.map(|x| x.to_string()),
+        kind: kind.into(),
+        value: entry.value.clone(),
+        created_at: entry.created_at,
+    }
+}
@@ -0,0 +1,233 @@
+use std::fmt;
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicU64, Ordering};
+use lru::LruCache;
+use parking_lot::Mutex;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use dal::{MetadataCache, Mysql};
+use crate::apis::youtube::{self, VideoResource, YouTubeApi, YouTubeError};
+use crate::config::Config;
+
+/// Hit and miss counters of a cache
+#[derive(Debug, Default)]
+pub struct CacheCounter {
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl CacheCounter {
+    fn hit(&self) {
+        self.hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn miss(&self) {
+        self.misses.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+}
+
+struct CacheEntry<T> {
+    value: T,
+    cached_at: i64,
+}
+
+/// A cache for one kind of metadata. Entries are kept in an in-memory LRU,
+/// and optionally persisted to the database so they survive a restart
+pub struct TieredCache<T> {
+    /// Prefix for the keys in the database, as all kinds of metadata share one table
+    prefix: &'static str,
+    memory: Mutex<LruCache<String, CacheEntry<T>>>,
+    /// Time to live of an entry in seconds
+    ttl: i64,
+    persistence: Option<MetadataCache<Mysql>>,
+    counter: CacheCounter,
+}
+
+impl<T: Clone + Serialize + DeserializeOwned> TieredCache<T> {
+    fn new(prefix: &'static str, capacity: usize, ttl: u64, persistence: Option<MetadataCache<Mysql>>) -> Self {
+        Self {
+            prefix,
+            memory: Mutex::new(LruCache::new(capacity)),
+            ttl: ttl as i64,
+            persistence,
+            counter: CacheCounter::default(),
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<T> {
+        let value = self.lookup(key, self.ttl);
+        match value {
+            Some(_) => self.counter.hit(),
+            None => self.counter.miss(),
+        }
+
+        value
+    }
+
+    /// Get an entry regardless of whether it has expired.
+    /// Used when the upstream provider is unavailable
+    fn get_stale(&self, key: &str) -> Option<T> {
+        self.lookup(key, i64::MAX)
+    }
+
+    fn lookup(&self, key: &str, max_age: i64) -> Option<T> {
+        let now = time::OffsetDateTime::now_utc().unix_timestamp();
+        if let Some(entry) = self.memory.lock().get(&key.to_string()) {
+            if now - entry.cached_at < max_age {
+                return Some(entry.value.clone());
+            }
+        }
+
+        let persistence = self.persistence.as_ref()?;
+        let cached = match persistence.get(format!("{}:{key}", self.prefix)) {
+            Ok(Some(x)) => x,
+            Ok(None) => return None,
+            Err(e) => {
+                warn!("Failed to read '{key}' from the {} cache: {e}", self.prefix);
+                return None;
+            }
+        };
+
+        if now - cached.cached_at >= max_age {
+            return None;
+        }
+
+        let value: T = match serde_json::from_str(&cached.data) {
+            Ok(x) => x,
+            Err(e) => {
+                warn!("Failed to deserialize '{key}' from the {} cache: {e}", self.prefix);
+                return None;
+            }
+        };
+
+        self.memory.lock().put(key.to_string(), CacheEntry {
+            value: value.clone(),
+            cached_at: cached.cached_at,
+        });
+
+        Some(value)
+    }
+
+    fn insert(&self, key: &str, value: T) {
+        let now = time::OffsetDateTime::now_utc().unix_timestamp();
+
+        if let Some(persistence) = &self.persistence {
+            let result = serde_json::to_string(&value)
+                .map_err(|e| e.to_string())
+                .and_then(|data| persistence.set(&format!("{}:{key}", self.prefix), &data, now).map_err(|e| e.to_string()));
+            if let Err(e) = result {
+                warn!("Failed to persist '{key}' to the {} cache: {e}", self.prefix);
+            }
+        }
+
+        self.memory.lock().put(key.to_string(), CacheEntry {
+            value,
+            cached_at: now,
+        });
+    }
+
+    pub fn counter(&self) -> &CacheCounter {
+        &self.counter
+    }
+
+    /// The amount of entries in memory
+    pub fn size(&self) -> usize {
+        self.memory.lock().len()
+    }
+}
+
+/// Cache for YouTube Data API responses, to save on API quota
+pub struct YouTubeMetadataCache {
+    /// Video resources, keyed by video ID
+    videos: TieredCache<VideoResource>,
+    /// Pages of search results, keyed by page size, page token and normalized query
+    searches: TieredCache<SearchResults>,
+}
+
+/// A page of search results
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SearchResults {
+    pub video_ids: Vec<String>,
+    pub next_page_token: Option<String>,
+}
+
+impl Debug for YouTubeMetadataCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("YouTubeMetadataCache")
+            .field("videos", &self.videos.counter)
+            .field("searches", &self.searches.counter)
+            .finish()
+    }
+}
+
+impl YouTubeMetadataCache {
+    pub fn new(config: &Config, dal: &Mysql) -> Self {
+        let persistence = || config.youtube_cache_persist.then(|| MetadataCache::new(dal.clone()));
+
+        Self {
+            videos: TieredCache::new("youtube-video", config.youtube_cache_capacity, config.youtube_video_cache_ttl, persistence()),
+            searches: TieredCache::new("youtube-search-page", config.youtube_cache_capacity, config.youtube_search_cache_ttl, persistence()),
+        }
+    }
+
+    /// Get a video, from the cache if possible.
+    /// If the API quota is exhausted, an expired entry is returned if one exists
+    pub async fn get_video(&self, api: &YouTubeApi, video_id: &str) -> youtube::Result<Option<VideoResource>> {
+        if let Some(video) = self.videos.get(video_id) {
+            return Ok(Some(video));
+        }
+
+        let video = match api.get_video(video_id).await {
+            Ok(x) => x,
+            Err(e @ YouTubeError::QuotaExceeded { .. }) => return self.videos.get_stale(video_id).map(Some).ok_or(e),
+            Err(e) => return Err(e),
+        };
+        if let Some(video) = &video {
+            self.videos.insert(video_id, video.clone());
+        }
+
+        Ok(video)
+    }
+
+    /// Search for videos, from the cache if possible.
+    /// If the API quota is exhausted, an expired entry is returned if one exists
+    pub async fn search(&self, api: &YouTubeApi, query: &str, limit: u32, page_token: Option<&str>) -> youtube::Result<SearchResults> {
+        let key = format!("{limit}:{}:{}", page_token.unwrap_or_default(), query.trim().to_lowercase());
+        if let Some(results) = self.searches.get(&key) {
+            return Ok(results);
+        }
+
+        let page = match api.search(query, limit, page_token).await {
+            Ok(x) => x,
+            Err(e @ YouTubeError::QuotaExceeded { .. }) => return self.searches.get_stale(&key).ok_or(e),
+            Err(e) => return Err(e),
+        };
+
+        let results = SearchResults {
+            video_ids: page.items.into_iter()
+                .map(|x| x.id.video_id)
+                .collect(),
+            next_page_token: page.next_page_token,
+        };
+        self.searches.insert(&key, results.clone());
+
+        Ok(results)
+    }
+
+    pub fn videos(&self) -> &TieredCache<VideoResource> {
+        &self.videos
+    }
+
+    pub fn searches(&self) -> &TieredCache<SearchResults> {
+        &self.searches
+    }
+}
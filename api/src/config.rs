@@ -9,10 +9,162 @@ pub struct Config {
     pub google_oauth_client_id: String,
     pub google_oauth_client_secret: String,
     pub google_api_key: String,
+    /// The maximum amount of tracks imported from a single playlist
+    #[serde(default = "default_playlist_import_limit")]
+    pub playlist_import_limit: usize,
+    /// The time in seconds after which an unused Pretrack is deleted
+    #[serde(default = "default_pretrack_ttl")]
+    pub pretrack_ttl: u64,
+    /// The interval in seconds at which expired Pretracks are deleted
+    #[serde(default = "default_pretrack_sweep_interval")]
+    pub pretrack_sweep_interval: u64,
+    /// The time in seconds YouTube video metadata is cached
+    #[serde(default = "default_youtube_video_cache_ttl")]
+    pub youtube_video_cache_ttl: u64,
+    /// The time in seconds YouTube search results are cached
+    #[serde(default = "default_youtube_search_cache_ttl")]
+    pub youtube_search_cache_ttl: u64,
+    /// The maximum amount of entries per kind of YouTube metadata kept in memory
+    #[serde(default = "default_youtube_cache_capacity")]
+    pub youtube_cache_capacity: usize,
+    /// Whether cached YouTube metadata should also be stored in the database
+    #[serde(default)]
+    pub youtube_cache_persist: bool,
+    /// The interval in seconds at which expired YouTube metadata is deleted from the database
+    #[serde(default = "default_youtube_cache_sweep_interval")]
+    pub youtube_cache_sweep_interval: u64,
+    /// Base URL of the YouTube Data API
+    #[serde(default = "default_youtube_api_base_url")]
+    pub youtube_api_base_url: String,
+    /// Timeout in seconds for requests to upstream providers
+    #[serde(default = "default_http_timeout")]
+    pub http_timeout: u64,
+    /// Timeout in seconds for connecting to upstream providers
+    #[serde(default = "default_http_connect_timeout")]
+    pub http_connect_timeout: u64,
+    /// Proxy used for all requests to upstream providers
+    pub http_proxy: Option<String>,
+    /// User agent used for all requests to upstream providers
+    #[serde(default = "default_http_user_agent")]
+    pub http_user_agent: String,
+    /// The daily quota in units of the YouTube Data API key
+    #[serde(default = "default_youtube_daily_quota")]
+    pub youtube_daily_quota: u64,
+    /// The interval in seconds at which the queues of Rooms with autoplay enabled are checked
+    #[serde(default = "default_auto_dj_interval")]
+    pub auto_dj_interval: u64,
+    /// The amount of tracks autoplay enqueues when a queue runs dry
+    #[serde(default = "default_auto_dj_batch_size")]
+    pub auto_dj_batch_size: usize,
+    /// The delay in milliseconds between starting playback and the moment devices should start playing,
+    /// giving them time to buffer
+    #[serde(default = "default_playback_start_delay")]
+    pub playback_start_delay: u64,
+    /// The time in seconds after which an active player device which sent no heartbeat is replaced
+    #[serde(default = "default_player_heartbeat_timeout")]
+    pub player_heartbeat_timeout: u64,
+    /// The interval in seconds at which player devices are checked for missed heartbeats
+    #[serde(default = "default_player_failover_interval")]
+    pub player_failover_interval: u64,
+    /// The time in seconds after which a player device which stopped sending heartbeats is deleted
+    #[serde(default = "default_player_device_ttl")]
+    pub player_device_ttl: u64,
+    /// Token granting access to administrative endpoints, such as the global blocklist.
+    /// If not set, administrative endpoints are disabled
+    pub admin_token: Option<String>,
+    /// The private VAPID key used to sign Web Push messages, base64url encoded.
+    /// If not set, Web Push is disabled
+    pub vapid_private_key: Option<String>,
+    /// The public VAPID key matching `vapid_private_key`, base64url encoded
+    pub vapid_public_key: Option<String>,
+    /// Contact for push services, e.g. `mailto:admin@example.com`
+    pub vapid_subject: Option<String>,
+    /// Send all Web Push messages to this URL instead of the push service of the subscription,
+    /// e.g. a local mock receiver
+    pub push_endpoint_override: Option<String>,
+    /// The time in seconds a push service should keep undelivered messages
+    #[serde(default = "default_push_ttl")]
+    pub push_ttl: u32,
 }
 
 impl Config {
     pub fn from_env() -> Result<Self, envy::Error> {
         envy::from_env::<Self>()
     }
+}
+
+fn default_playlist_import_limit() -> usize {
+    100
+}
+
+fn default_pretrack_ttl() -> u64 {
+    3600
+}
+
+fn default_pretrack_sweep_interval() -> u64 {
+    300
+}
+
+fn default_youtube_video_cache_ttl() -> u64 {
+    86400
+}
+
+fn default_youtube_search_cache_ttl() -> u64 {
+    3600
+}
+
+fn default_youtube_cache_capacity() -> usize {
+    1000
+}
+
+fn default_youtube_cache_sweep_interval() -> u64 {
+    3600
+}
+
+fn default_youtube_api_base_url() -> String {
+    "https://www.googleapis.com/youtube/v3".into()
+}
+
+fn default_http_timeout() -> u64 {
+    10
+}
+
+fn default_http_connect_timeout() -> u64 {
+    5
+}
+
+fn default_http_user_agent() -> String {
+    format!("{}/{}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))
+}
+
+fn default_youtube_daily_quota() -> u64 {
+    10000
+}
+
+fn default_auto_dj_interval() -> u64 {
+    15
+}
+
+fn default_auto_dj_batch_size() -> usize {
+    3
+}
+
+fn default_playback_start_delay() -> u64 {
+    500
+}
+
+fn default_player_heartbeat_timeout() -> u64 {
+    15
+}
+
+fn default_player_failover_interval() -> u64 {
+    5
+}
+
+fn default_player_device_ttl() -> u64 {
+    3600
+}
+
+fn default_push_ttl() -> u32 {
+    300
 }
\ No newline at end of file
@@ -1,8 +1,9 @@
 use actix_web::{HttpResponse, ResponseError};
 use actix_web::body::BoxBody;
-use actix_web::http::StatusCode;
+use actix_web::http::{header, StatusCode};
 use thiserror::Error;
 use dal::Error as DalError;
+use crate::apis::youtube::{Unplayable, YouTubeError};
 
 pub type WebResult<T> = Result<T, Error>;
 
@@ -23,10 +24,18 @@ pub enum Error {
     Sse(#[from] crate::services::sse::broadcaster::SseError),
     #[error("Forbidden: {0}")]
     Forbidden(&'static str),
+    #[error("Unauthorized: {0}")]
+    Unauthorized(&'static str),
+    #[error("Too many requests: {0}")]
+    LimitExceeded(#[from] dal::LimitExceeded),
     #[error("Requwest error: {0}")]
     Reqwest(#[from] reqwest::Error),
     #[error("Join error: {0}")]
     TokioJoin(#[from] tokio::task::JoinError),
+    #[error("Upstream provider error: {0}")]
+    Upstream(#[from] YouTubeError),
+    #[error("Unplayable: {0}")]
+    Unplayable(#[from] Unplayable),
 }
 
 impl ResponseError for Error {
@@ -44,19 +53,40 @@ impl ResponseError for Error {
             Self::NotFound(_) => StatusCode::NOT_FOUND,
             Self::BadRequest(_) | Self::Uuid(_) => StatusCode::BAD_REQUEST,
             Self::Forbidden(_) => StatusCode::FORBIDDEN,
+            Self::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            Self::LimitExceeded(_) => StatusCode::TOO_MANY_REQUESTS,
             Self::Reqwest(_) => StatusCode::INTERNAL_SERVER_ERROR,
             Self::TokioJoin(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::Upstream(YouTubeError::QuotaExceeded { .. }) => StatusCode::SERVICE_UNAVAILABLE,
+            Self::Upstream(_) => StatusCode::BAD_GATEWAY,
+            Self::Unplayable(_) => StatusCode::UNPROCESSABLE_ENTITY,
         }
     }
 
     fn error_response(&self) -> HttpResponse<BoxBody> {
-        let body = if self.status_code().is_client_error() {
+        let retry_after = self.retry_after();
+        let body = if self.status_code().is_client_error() || retry_after.is_some() {
             format!("{self}")
         } else {
             self.status_code().canonical_reason().unwrap_or("").to_string()
         };
 
-        HttpResponse::build(self.status_code())
-            .body(body)
+        let mut response = HttpResponse::build(self.status_code());
+        if let Some(retry_after) = retry_after {
+            response.insert_header((header::RETRY_AFTER, retry_after.to_string()));
+        }
+
+        response.body(body)
+    }
+}
+
+impl Error {
+    /// The amount of seconds after which the client may retry, if applicable
+    fn retry_after(&self) -> Option<u64> {
+        match self {
+            Self::Upstream(YouTubeError::QuotaExceeded { retry_after }) => Some(*retry_after),
+            Self::LimitExceeded(dal::LimitExceeded::Cooldown { retry_after }) => Some(*retry_after),
+            _ => None,
+        }
     }
 }
\ No newline at end of file
@@ -9,6 +9,15 @@ mod appdata;
 mod services;
 mod error;
 mod apis;
+mod tasks;
+mod cache;
+mod auto_dj;
+mod playback;
+mod player;
+mod blocklist;
+mod notify;
+mod push;
+mod presence;
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
@@ -24,8 +33,17 @@ async fn main() -> std::io::Result<()> {
     info!("Applying database migrations");
     appdata.migrate().expect("Applying migrations");
 
-    debug!("Configuring Actix");
     let appdata_arc = Arc::new(appdata);
+
+    debug!("Spawning background tasks");
+    tasks::pretrack_sweeper::spawn(appdata_arc.clone());
+    tasks::auto_dj::spawn(appdata_arc.clone());
+    tasks::player_failover::spawn(appdata_arc.clone());
+    if appdata_arc.config.youtube_cache_persist {
+        tasks::metadata_cache_sweeper::spawn(appdata_arc.clone());
+    }
+
+    debug!("Configuring Actix");
     let server = HttpServer::new(move || App::new()
         .app_data(web::Data::new(appdata_arc.clone()))
         .wrap(TracingLogger::default())
@@ -35,12 +53,37 @@ async fn main() -> std::io::Result<()> {
             .route("/room/join", web::post().to(services::room::join::join))
             .route("/room/leave", web::post().to(services::room::leave::leave))
             .route("/room/members", web::get().to(services::room::members::members))
+            .route("/room/settings", web::post().to(services::room::settings::settings))
+            .route("/room/co-host", web::post().to(services::room::co_host::co_host))
             .route("/tracks/sse-list/{uuid}", web::get().to(services::tracks::sse_list::sse_list))
             .route("/tracks/list/{uuid}", web::get().to(services::tracks::list::list))
             .route("/tracks/add", web::post().to(services::tracks::add::add))
             .route("/tracks/find/youtube", web::post().to(services::tracks::find::youtube::youtube))
+            .route("/tracks/find/url", web::post().to(services::tracks::find::url::url))
             .route("/tracks/find/search", web::get().to(services::tracks::find::search::search))
-            .route("/user/get/{uuid}", web::get().to(services::user::get::get))))
+            .route("/tracks/remove", web::post().to(services::tracks::remove::remove))
+            .route("/tracks/mine", web::get().to(services::tracks::mine::mine))
+            .route("/tracks/approve", web::post().to(services::tracks::moderate::approve))
+            .route("/tracks/reject", web::post().to(services::tracks::moderate::reject))
+            .route("/tracks/skip-vote", web::post().to(services::tracks::skip_vote::skip_vote))
+            .route("/tracks/import/youtube-playlist", web::post().to(services::tracks::import::youtube_playlist::youtube_playlist))
+            .route("/playback/time", web::get().to(services::playback::time::time))
+            .route("/playback/get/{uuid}", web::get().to(services::playback::get::get))
+            .route("/playback/control", web::post().to(services::playback::control::control))
+            .route("/player/register", web::post().to(services::player::register::register))
+            .route("/player/heartbeat", web::post().to(services::player::heartbeat::heartbeat))
+            .route("/player/unregister", web::post().to(services::player::unregister::unregister))
+            .route("/blocklist/add", web::post().to(services::blocklist::add::add))
+            .route("/blocklist/remove", web::post().to(services::blocklist::remove::remove))
+            .route("/blocklist/list", web::get().to(services::blocklist::list::list))
+            .route("/user/get/{uuid}", web::get().to(services::user::get::get))
+            .route("/user/rooms", web::get().to(services::user::rooms::rooms))
+            .route("/user/sse/{uuid}", web::get().to(services::user::sse::sse))
+            .route("/push/vapid-key", web::get().to(services::push::vapid_key::vapid_key))
+            .route("/push/subscribe", web::post().to(services::push::subscribe::subscribe))
+            .route("/push/unsubscribe", web::post().to(services::push::unsubscribe::unsubscribe))
+            .route("/status/cache", web::get().to(services::status::cache::cache))
+            .route("/status/quota", web::get().to(services::status::quota::quota))))
         .bind("[::]:8080")?
         .run();
 
@@ -0,0 +1,69 @@
+use dal::{Mysql, Room, RoomExt, Track, TrackExt, SYSTEM_USER_UUID};
+use dal::uuid::Uuid;
+use proto::{SsePacketEvent, UserNotification, UserNotificationKind};
+use tracing::warn;
+use crate::appdata::AppData;
+use crate::error::WebResult;
+use crate::playback;
+use crate::services::tracks::list::track_to_proto;
+
+/// Send a notification to a User over their own event stream and through Web Push.
+/// Notifications for the system user are dropped.
+///
+/// Notifications are sent after the change they describe is stored, so failures are logged rather than returned
+pub fn send(appdata: &AppData, user_uuid: Uuid, notification: UserNotification) {
+    if let Err(e) = try_send(appdata, user_uuid, notification) {
+        warn!("Failed to notify User {user_uuid}: {e}");
+    }
+}
+
+/// Let the User who added a Track know it plays after the current one
+pub fn track_up_next(appdata: &AppData, room: &Room<Mysql>, track: &Track<Mysql>) {
+    if let Err(e) = try_track_up_next(appdata, room, track, false) {
+        warn!("Failed to notify about Track {} being up next: {e}", track.uuid);
+    }
+}
+
+/// Let the User who added a Track know it plays after the current one, if it does.
+/// Used when a Track enters the queue
+pub fn track_up_next_if_next(appdata: &AppData, room: &Room<Mysql>, track: &Track<Mysql>) {
+    if let Err(e) = try_track_up_next(appdata, room, track, true) {
+        warn!("Failed to notify about Track {} being up next: {e}", track.uuid);
+    }
+}
+
+fn try_track_up_next(appdata: &AppData, room: &Room<Mysql>, track: &Track<Mysql>, check: bool) -> WebResult<()> {
+    if check && playback::up_next(room, &room.get_playback()?)?.map(|x| x.uuid) != Some(track.uuid) {
+        return Ok(());
+    }
+
+    let user_uuid = match track.get_added_by()? {
+        Some(x) => x,
+        None => return Ok(())
+    };
+
+    try_send(appdata, user_uuid, UserNotification {
+        kind: UserNotificationKind::UpNext.into(),
+        room_uuid: room.uuid.to_string(),
+        track: Some(track_to_proto(&appdata.dal, track)?),
+        reason: None,
+    })
+}
+
+fn try_send(appdata: &AppData, user_uuid: Uuid, notification: UserNotification) -> WebResult<()> {
+    if user_uuid.eq(&SYSTEM_USER_UUID) {
+        return Ok(());
+    }
+
+    if let Some(web_push) = &appdata.web_push {
+        web_push.send(user_uuid, notification.clone());
+    }
+
+    let sse = appdata.sse_user.lock();
+    if let Some(broadcaster) = sse.get(&user_uuid) {
+        let lock = broadcaster.lock();
+        lock.send(SsePacketEvent::Notification, notification)?;
+    }
+
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+use dal::{Dal, Datastore, Mysql, Playback, PlaybackStatus, Room, RoomExt, Track};
+use dal::uuid::Uuid;
+use proto::{PlaybackStateResponse, SsePacketEvent};
+use tracing::warn;
+use crate::appdata::AppData;
+use crate::auto_dj;
+use crate::notify;
+use crate::error::WebResult;
+
+/// The current UNIX timestamp in milliseconds
+pub fn now_millis() -> i64 {
+    (time::OffsetDateTime::now_utc().unix_timestamp_nanos() / 1_000_000) as i64
+}
+
+/// The server time at which playback should start when started now.
+/// This leaves devices time to buffer, so they all start in sync
+pub fn start_time(appdata: &AppData) -> i64 {
+    now_millis() + appdata.config.playback_start_delay as i64
+}
+
+/// Start playing a Track from the beginning
+pub fn start(appdata: &AppData, playback: &mut Playback<Mysql>, track: &Track<Mysql>) {
+    playback.track_uuid = Some(track.uuid);
+    playback.status = PlaybackStatus::Playing;
+    playback.position = 0;
+    playback.reference_time = start_time(appdata);
+}
+
+/// Remove the current Track from the queue and start playing the next one.
+/// If the queue has run dry and autoplay is enabled, it is filled first.
+/// Playback stops if there is no next Track
+pub async fn advance(appdata: &AppData, room: &Room<Mysql>, playback: &mut Playback<Mysql>) -> WebResult<()> {
+    let queue = room.get_queue()?;
+    if let Some(track_uuid) = playback.track_uuid {
+        room.get_skip_votes(track_uuid)?.clear()?;
+        if let Some(track) = Track::get(appdata.dal.clone(), track_uuid)? {
+            queue.dequeue(&track)?;
+        }
+    }
+
+    let mut next = queue.get_enqueued()?.into_iter().next();
+    if next.is_none() && room.autoplay {
+        if let Err(e) = auto_dj::fill(appdata, room).await {
+            warn!("Failed to autoplay in Room {}: {e}", room.uuid);
+        }
+        next = queue.get_enqueued()?.into_iter().next();
+    }
+
+    match next {
+        Some(track) => start(appdata, playback, &track),
+        None => {
+            playback.track_uuid = None;
+            playback.status = PlaybackStatus::Stopped;
+            playback.position = 0;
+            playback.reference_time = now_millis();
+        }
+    }
+
+    if let Some(track) = up_next(room, playback)? {
+        notify::track_up_next(appdata, room, &track);
+    }
+
+    Ok(())
+}
+
+/// The Track which plays after the current one, if any
+pub fn up_next(room: &Room<Mysql>, playback: &Playback<Mysql>) -> WebResult<Option<Track<Mysql>>> {
+    let playing = match playback.track_uuid {
+        Some(x) => x,
+        None => return Ok(None)
+    };
+
+    Ok(room.get_queue()?.get_enqueued()?
+        .into_iter()
+        .skip_while(|x| x.uuid.ne(&playing))
+        .nth(1))
+}
+
+/// When the enqueued Tracks of a Room are expected to play
+pub struct Schedule {
+    /// Estimated UNIX timestamp at which each Track which is not playing yet starts
+    pub start_times: HashMap<Uuid, i64>,
+    /// The time in seconds until every enqueued Track has played
+    pub remaining: i64,
+}
+
+/// Estimate when the enqueued Tracks of a Room will play, based on the current playback position
+/// and the durations of the Tracks ahead. If playback is paused, it is assumed to resume now
+pub fn schedule(room: &Room<Mysql>) -> WebResult<Schedule> {
+    let playback = room.get_playback()?;
+    let tracks = room.get_queue()?.get_enqueued()?
+        .into_iter()
+        .map(|x| (x.uuid, x.duration))
+        .collect::<Vec<_>>();
+
+    Ok(schedule_at(now_millis(), &playback, &tracks))
+}
+
+/// Estimate when the provided Tracks will play at UNIX timestamp `now` in milliseconds.
+/// `tracks` are the UUIDs and durations in seconds of the enqueued Tracks, in order
+fn schedule_at<T: Datastore>(now: i64, playback: &Playback<T>, tracks: &[(Uuid, i64)]) -> Schedule {
+    let mut time = now;
+    let mut start_times = HashMap::new();
+    for &(uuid, duration) in tracks {
+        let duration = duration * 1000;
+        if playback.track_uuid == Some(uuid) && playback.status != PlaybackStatus::Stopped {
+            // Playback may be scheduled to start slightly in the future
+            let delay = match playback.status {
+                PlaybackStatus::Playing => (playback.reference_time - now).max(0),
+                _ => 0,
+            };
+            time += delay + (duration - playback.position_at(now)).max(0);
+            continue;
+        }
+
+        start_times.insert(uuid, time / 1000);
+        time += duration;
+    }
+
+    Schedule {
+        start_times,
+        remaining: (time - now) / 1000,
+    }
+}
+
+/// Store the playback state and notify the Room
+pub fn save(appdata: &AppData, playback: &Playback<Mysql>) -> WebResult<PlaybackStateResponse> {
+    playback.save()?;
+
+    let state = to_proto(playback);
+    let sse = appdata.sse_track_list.lock();
+    if let Some(broadcaster) = sse.get(&playback.room_uuid) {
+        let lock = broadcaster.lock();
+        lock.send(SsePacketEvent::Playback, state.clone())?;
+    }
+
+    Ok(state)
+}
+
+pub fn to_proto(playback: &Playback<Mysql>) -> PlaybackStateResponse {
+    let status = match playback.status {
+        PlaybackStatus::Stopped => proto::PlaybackStatus::Stopped,
+        PlaybackStatus::Playing => proto::PlaybackStatus::Playing,
+        PlaybackStatus::Paused => proto::PlaybackStatus::Paused,
+    };
+
+    PlaybackStateResponse {
+        room_uuid: playback.room_uuid.to_string(),
+        track_uuid: playback.track_uuid.map(|x| x.to_string()),
+        status: status.into(),
+        position: playback.position,
+        reference_time: playback.reference_time,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct TestStore;
+
+    impl Datastore for TestStore {}
+
+    const NOW: i64 = 1_000_000;
+
+    fn playback(track_uuid: Uuid, status: PlaybackStatus, position: i64, reference_time: i64) -> Playback<TestStore> {
+        let mut playback = Playback::stopped(TestStore, Uuid::new_v4());
+        playback.track_uuid = Some(track_uuid);
+        playback.status = status;
+        playback.position = position;
+        playback.reference_time = reference_time;
+        playback
+    }
+
+    #[test]
+    fn schedule_stopped() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let schedule = schedule_at(NOW, &Playback::stopped(TestStore, Uuid::new_v4()), &[(a, 60), (b, 120)]);
+        assert_eq!(schedule.start_times.get(&a), Some(&1_000));
+        assert_eq!(schedule.start_times.get(&b), Some(&1_060));
+        assert_eq!(schedule.remaining, 180);
+    }
+
+    #[test]
+    fn schedule_playing() {
+        // 15 seconds into the current Track
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let schedule = schedule_at(NOW, &playback(a, PlaybackStatus::Playing, 10_000, NOW - 5_000), &[(a, 60), (b, 120)]);
+        assert_eq!(schedule.start_times.get(&a), None);
+        assert_eq!(schedule.start_times.get(&b), Some(&1_045));
+        assert_eq!(schedule.remaining, 165);
+    }
+
+    #[test]
+    fn schedule_playing_starts_later() {
+        // Playback starts in 2 seconds, once devices have buffered
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let schedule = schedule_at(NOW, &playback(a, PlaybackStatus::Playing, 0, NOW + 2_000), &[(a, 60), (b, 120)]);
+        assert_eq!(schedule.start_times.get(&b), Some(&1_062));
+        assert_eq!(schedule.remaining, 182);
+    }
+
+    #[test]
+    fn schedule_paused() {
+        // Paused playback is assumed to resume now
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let schedule = schedule_at(NOW, &playback(a, PlaybackStatus::Paused, 30_000, NOW - 100_000), &[(a, 60), (b, 120)]);
+        assert_eq!(schedule.start_times.get(&b), Some(&1_030));
+        assert_eq!(schedule.remaining, 150);
+    }
+}
\ No newline at end of file
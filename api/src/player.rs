@@ -0,0 +1,74 @@
+use dal::{Dal, Mysql, PlayerDevice, PlayerDeviceExt, Room, RoomExt};
+use dal::uuid::Uuid;
+use proto::{PlayerActiveResponse, SsePacketEvent};
+use tracing::debug;
+use crate::appdata::AppData;
+use crate::error::WebResult;
+
+/// The UNIX timestamp before which a device's last heartbeat must lie for it to be considered gone
+fn heartbeat_cutoff(appdata: &AppData) -> i64 {
+    time::OffsetDateTime::now_utc().unix_timestamp() - appdata.config.player_heartbeat_timeout as i64
+}
+
+/// Make a device the active device of its Room and notify the Room
+pub fn activate(appdata: &AppData, device: &mut PlayerDevice<Mysql>) -> WebResult<()> {
+    device.activate()?;
+    debug!("Player device {} is now active in Room {}", device.uuid, device.room_uuid);
+    broadcast(appdata, device.room_uuid, Some(device))
+}
+
+/// Activate the standby device of a Room which registered first and is still sending heartbeats.
+/// If there is none, the Room is left without an active device
+pub fn promote_standby(appdata: &AppData, room: &Room<Mysql>) -> WebResult<()> {
+    let cutoff = heartbeat_cutoff(appdata);
+    let standby = room.list_player_devices()?
+        .into_iter()
+        .find(|x| !x.active && x.last_heartbeat >= cutoff);
+
+    match standby {
+        Some(mut device) => activate(appdata, &mut device),
+        None => {
+            debug!("No standby player device available in Room {}", room.uuid);
+            broadcast(appdata, room.uuid, None)
+        }
+    }
+}
+
+/// Replace an active device which missed its heartbeats
+pub fn failover(appdata: &AppData, mut device: PlayerDevice<Mysql>) -> WebResult<()> {
+    device.active = false;
+    device.update()?;
+
+    let room = match Room::get(appdata.dal.clone(), device.room_uuid)? {
+        Some(x) => x,
+        None => return Ok(())
+    };
+
+    promote_standby(appdata, &room)
+}
+
+pub fn get_active(room: &Room<Mysql>) -> WebResult<Option<PlayerDevice<Mysql>>> {
+    Ok(room.list_player_devices()?.into_iter().find(|x| x.active))
+}
+
+fn broadcast(appdata: &AppData, room_uuid: Uuid, device: Option<&PlayerDevice<Mysql>>) -> WebResult<()> {
+    let sse = appdata.sse_track_list.lock();
+    if let Some(broadcaster) = sse.get(&room_uuid) {
+        let lock = broadcaster.lock();
+        lock.send(SsePacketEvent::ActivePlayer, PlayerActiveResponse {
+            device: device.map(to_proto)
+        })?;
+    }
+
+    Ok(())
+}
+
+pub fn to_proto(device: &PlayerDevice<Mysql>) -> proto::PlayerDevice {
+    proto::PlayerDevice {
+        device_uuid: device.uuid.to_string(),
+        user_uuid: device.user_uuid.to_string(),
+        name: device.name.clone(),
+        active: device.active,
+        last_heartbeat: device.last_heartbeat,
+    }
+}
@@ -0,0 +1,25 @@
+use std::collections::HashSet;
+use dal::{Member, Mysql, Room, RoomExt};
+use crate::appdata::AppData;
+use crate::error::WebResult;
+
+/// List the members of a Room which are currently connected, as opposed to members who closed the app without leaving.
+/// A member is connected if they are listening on their own event stream, or have a player device which recently sent a heartbeat
+pub fn present_members(appdata: &AppData, room: &Room<Mysql>) -> WebResult<Vec<Member>> {
+    let heartbeat_after = time::OffsetDateTime::now_utc().unix_timestamp() - appdata.config.player_heartbeat_timeout as i64;
+    let playing = room.list_player_devices()?
+        .into_iter()
+        .filter(|x| x.last_heartbeat >= heartbeat_after)
+        .map(|x| x.user_uuid)
+        .collect::<HashSet<_>>();
+
+    let sse = appdata.sse_user.lock();
+    let members = room.list_members()?
+        .into_iter()
+        .filter(|x| playing.contains(&x.uuid) || sse.get(&x.uuid)
+            .map(|broadcaster| broadcaster.lock().has_clients())
+            .unwrap_or(false))
+        .collect();
+
+    Ok(members)
+}
\ No newline at end of file
@@ -0,0 +1,124 @@
+use dal::{Dal, Mysql, PushSubscription, PushSubscriptionExt};
+use dal::uuid::Uuid;
+use proto::UserNotification;
+use reqwest::StatusCode;
+use thiserror::Error;
+use tracing::{debug, warn};
+use web_push::{ContentEncoding, SubscriptionInfo, VapidSignatureBuilder, WebPushError, WebPushMessage, WebPushMessageBuilder, URL_SAFE_NO_PAD};
+use crate::config::Config;
+
+#[derive(Debug, Error)]
+pub enum PushError {
+    #[error("Dal error: {0}")]
+    Dal(#[from] dal::Error),
+    #[error("Web Push error: {0}")]
+    WebPush(#[from] WebPushError),
+    #[error("Reqwest error: {0}")]
+    Reqwest(#[from] reqwest::Error),
+    #[error("Failed to serialize to json: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Push service responded with {0}")]
+    Status(StatusCode),
+}
+
+/// Delivers notifications to the browsers of Users through Web Push
+#[derive(Debug, Clone)]
+pub struct WebPush {
+    dal: Mysql,
+    client: reqwest::Client,
+    private_key: String,
+    subject: Option<String>,
+    endpoint_override: Option<String>,
+    ttl: u32,
+}
+
+impl WebPush {
+    /// Returns `None` if no VAPID key is configured
+    pub fn new(config: &Config, dal: Mysql, client: reqwest::Client) -> Option<Self> {
+        Some(Self {
+            dal,
+            client,
+            private_key: config.vapid_private_key.clone()?,
+            subject: config.vapid_subject.clone(),
+            endpoint_override: config.push_endpoint_override.clone(),
+            ttl: config.push_ttl,
+        })
+    }
+
+    /// Deliver a notification to all push subscriptions of a User in the background
+    pub fn send(&self, user_uuid: Uuid, notification: UserNotification) {
+        let this = self.clone();
+        actix_rt::spawn(async move {
+            if let Err(e) = this.deliver(user_uuid, &notification).await {
+                warn!("Failed to push notification to User {user_uuid}: {e}");
+            }
+        });
+    }
+
+    async fn deliver(&self, user_uuid: Uuid, notification: &UserNotification) -> Result<(), PushError> {
+        let content = serde_json::to_vec(notification)?;
+        for subscription in PushSubscription::list(self.dal.clone(), user_uuid)? {
+            match self.deliver_to(&subscription, &content).await {
+                Ok(()) => {},
+                Err(PushError::Status(StatusCode::NOT_FOUND | StatusCode::GONE)) => {
+                    // The browser unsubscribed, the subscription will never work again
+                    debug!("Removing expired push subscription {}", subscription.uuid);
+                    subscription.delete()?;
+                },
+                Err(e) => warn!("Failed to push to subscription {}: {e}", subscription.uuid),
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn deliver_to(&self, subscription: &PushSubscription<Mysql>, content: &[u8]) -> Result<(), PushError> {
+        let message = self.build_message(subscription, content)?;
+        let endpoint = match &self.endpoint_override {
+            Some(x) => x.clone(),
+            None => message.endpoint.to_string(),
+        };
+
+        let mut request = self.client.post(endpoint)
+            .header("TTL", message.ttl.to_string());
+        if let Some(payload) = message.payload {
+            request = request
+                .header("Content-Encoding", payload.content_encoding)
+                .header("Content-Type", "application/octet-stream");
+            for (name, value) in payload.crypto_headers {
+                request = request.header(name, value);
+            }
+            request = request.body(payload.content);
+        }
+
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            return Err(PushError::Status(response.status()));
+        }
+
+        Ok(())
+    }
+
+    fn build_message(&self, subscription: &PushSubscription<Mysql>, content: &[u8]) -> Result<WebPushMessage, PushError> {
+        let info = SubscriptionInfo::new(&subscription.endpoint, &subscription.p256dh, &subscription.auth);
+
+        let mut signature = VapidSignatureBuilder::from_base64(&self.private_key, URL_SAFE_NO_PAD, &info)?;
+        if let Some(subject) = &self.subject {
+            signature.add_claim("sub", subject.as_str());
+        }
+
+        let mut builder = WebPushMessageBuilder::new(&info)?;
+        builder.set_ttl(self.ttl);
+        builder.set_payload(ContentEncoding::Aes128Gcm, content);
+        builder.set_vapid_signature(signature.build()?);
+        Ok(builder.build()?)
+    }
+}
+
+pub fn to_proto(subscription: &PushSubscription<Mysql>) -> proto::PushSubscription {
+    proto::PushSubscription {
+        uuid: subscription.uuid.to_string(),
+        endpoint: subscription.endpoint.clone(),
+        created_at: subscription.created_at,
+    }
+}
\ No newline at end of file
@@ -0,0 +1,47 @@
+use std::future::Future;
+use std::ops::Deref;
+use std::pin::Pin;
+use actix_web::{FromRequest, HttpRequest};
+use actix_web::dev::Payload;
+use actix_web::http::header;
+use dal::constant_time_eq;
+use crate::appdata::WebData;
+
+/// Whether the request carries the admin token as a bearer token in the `Authorization` header.
+/// Requests are never authorized as admin if no admin token is configured
+#[derive(Debug, Clone, Copy)]
+pub struct Admin(bool);
+
+impl Deref for Admin {
+    type Target = bool;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl FromRequest for Admin {
+    type Error = crate::error::Error;
+    type Future = Pin<Box<dyn Future<Output=Result<Self, Self::Error>>>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let req = req.clone();
+        Box::pin(async move {
+            let admin_token = req.app_data::<WebData>()
+                .and_then(|x| x.config.admin_token.clone());
+            let authorized = match (admin_token, get_bearer_token(&req)) {
+                (Some(admin_token), Some(token)) => constant_time_eq(&admin_token, token),
+                _ => false,
+            };
+
+            Ok(Self(authorized))
+        })
+    }
+}
+
+/// Get the bearer token provided in the `Authorization` header, if any
+fn get_bearer_token(req: &HttpRequest) -> Option<&str> {
+    let header_value = req.headers().get(header::AUTHORIZATION)?;
+    let as_string = header_value.to_str().ok()?;
+    as_string.strip_prefix("Bearer ")
+}
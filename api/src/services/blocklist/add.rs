@@ -0,0 +1,39 @@
+use dal::{BlocklistEntry, BlocklistEntryBuildable, BlocklistKind, Dal, MAX_BLOCKLIST_VALUE_LENGTH};
+use proto::{BlocklistAddRequest, BlocklistAddResponse};
+use crate::appdata::WebData;
+use crate::blocklist::{self, authorize};
+use crate::error::{Error, WebResult};
+use crate::services::admin::Admin;
+use actix_multiresponse::Payload;
+use tracing::instrument;
+
+#[instrument]
+pub async fn add(data: WebData, admin: Admin, payload: Payload<BlocklistAddRequest>) -> WebResult<Payload<BlocklistAddResponse>> {
+    let room_uuid = authorize(&data.dal, admin, payload.room_uuid.as_deref(), payload.user_uuid.as_deref())?;
+
+    let kind = match proto::BlocklistKind::from_i32(payload.kind) {
+        Some(proto::BlocklistKind::PlatformId) => BlocklistKind::PlatformId,
+        Some(proto::BlocklistKind::Artist) => BlocklistKind::Artist,
+        Some(proto::BlocklistKind::Keyword) => BlocklistKind::Keyword,
+        None => return Err(Error::BadRequest("Unknown blocklist kind")),
+    };
+
+    let value = payload.value.trim();
+    if value.is_empty() {
+        return Err(Error::BadRequest("Blocklist value may not be empty"));
+    }
+
+    if value.chars().count() > MAX_BLOCKLIST_VALUE_LENGTH {
+        return Err(Error::BadRequest("Blocklist value may not be longer than 256 characters"));
+    }
+
+    let entry = BlocklistEntry::create(data.dal.clone(), BlocklistEntryBuildable {
+        room_uuid,
+        kind,
+        value: value.to_string(),
+    })?;
+
+    Ok(Payload(BlocklistAddResponse {
+        entry: Some(blocklist::to_proto(&entry))
+    }))
+}
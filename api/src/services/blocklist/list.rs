@@ -0,0 +1,31 @@
+use actix_web::web;
+use serde::Deserialize;
+use dal::{BlocklistEntry, BlocklistEntryExt};
+use proto::BlocklistListResponse;
+use crate::appdata::WebData;
+use crate::blocklist::{self, authorize};
+use crate::error::WebResult;
+use crate::services::admin::Admin;
+use actix_multiresponse::Payload;
+use tracing::instrument;
+
+#[derive(Deserialize, Debug)]
+pub struct Query {
+    /// If not provided, the global blocklist is listed
+    room_uuid: Option<String>,
+    user_uuid: Option<String>,
+}
+
+#[instrument]
+pub async fn list(data: WebData, admin: Admin, query: web::Query<Query>) -> WebResult<Payload<BlocklistListResponse>> {
+    let room_uuid = authorize(&data.dal, admin, query.room_uuid.as_deref(), query.user_uuid.as_deref())?;
+
+    let entries = BlocklistEntry::list(data.dal.clone(), room_uuid)?
+        .iter()
+        .map(blocklist::to_proto)
+        .collect::<Vec<_>>();
+
+    Ok(Payload(BlocklistListResponse {
+        entries
+    }))
+}
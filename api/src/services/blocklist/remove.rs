@@ -0,0 +1,29 @@
+use dal::{BlocklistEntry, BlocklistEntryExt, Dal};
+use dal::uuid::Uuid;
+use proto::{BlocklistListResponse, BlocklistRemoveRequest};
+use crate::appdata::WebData;
+use crate::blocklist::{self, authorize};
+use crate::error::{Error, WebResult};
+use crate::services::admin::Admin;
+use actix_multiresponse::Payload;
+use tracing::instrument;
+
+#[instrument]
+pub async fn remove(data: WebData, admin: Admin, payload: Payload<BlocklistRemoveRequest>) -> WebResult<Payload<BlocklistListResponse>> {
+    let room_uuid = authorize(&data.dal, admin, payload.room_uuid.as_deref(), payload.user_uuid.as_deref())?;
+
+    let entry = match BlocklistEntry::get(data.dal.clone(), Uuid::parse_str(&payload.entry_uuid)?)? {
+        Some(x) if x.room_uuid.eq(&room_uuid) => x,
+        _ => return Err(Error::NotFound("The requested blocklist entry does not exist"))
+    };
+    entry.delete()?;
+
+    let entries = BlocklistEntry::list(data.dal.clone(), room_uuid)?
+        .iter()
+        .map(blocklist::to_proto)
+        .collect::<Vec<_>>();
+
+    Ok(Payload(BlocklistListResponse {
+        entries
+    }))
+}
@@ -2,6 +2,12 @@ pub mod room;
 pub mod tracks;
 pub mod user;
 pub mod sse;
+pub mod status;
+pub mod playback;
+pub mod player;
+pub mod admin;
+pub mod blocklist;
+pub mod push;
 
 //mod payload;
 
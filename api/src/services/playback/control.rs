@@ -0,0 +1,66 @@
+use dal::{Dal, PlaybackStatus, Room, RoomExt, Track, uuid::Uuid};
+use proto::{PlaybackAction, PlaybackControlRequest, PlaybackStateResponse};
+use crate::appdata::WebData;
+use crate::error::{Error, WebResult};
+use crate::playback::{self, now_millis};
+use actix_multiresponse::Payload;
+use tracing::instrument;
+
+#[instrument]
+pub async fn control(data: WebData, payload: Payload<PlaybackControlRequest>) -> WebResult<Payload<PlaybackStateResponse>> {
+    let room = match Room::get(data.dal.clone(), Uuid::parse_str(&payload.room_uuid)?)? {
+        Some(x) => x,
+        None => return Err(Error::NotFound("The requested room does not exist"))
+    };
+
+    if room.owner.ne(&Uuid::parse_str(&payload.user_uuid)?) {
+        return Err(Error::Forbidden("Only the owner of the Room may control playback"));
+    }
+
+    let action = PlaybackAction::from_i32(payload.action)
+        .ok_or(Error::BadRequest("Unknown playback action"))?;
+
+    let now = now_millis();
+    let mut playback = room.get_playback()?;
+    match action {
+        PlaybackAction::Play => {
+            if playback.track_uuid.is_none() {
+                let track = match room.get_queue()?.get_enqueued()?.into_iter().next() {
+                    Some(x) => x,
+                    None => return Err(Error::Conflict("The queue is empty".to_string()))
+                };
+                playback::start(&data, &mut playback, &track);
+            } else if playback.status != PlaybackStatus::Playing {
+                playback.status = PlaybackStatus::Playing;
+                playback.reference_time = playback::start_time(&data);
+            }
+        },
+        PlaybackAction::Pause => {
+            if playback.status == PlaybackStatus::Playing {
+                playback.position = playback.position_at(now);
+                playback.status = PlaybackStatus::Paused;
+                playback.reference_time = now;
+            }
+        },
+        PlaybackAction::Seek => {
+            let position = payload.position.ok_or(Error::BadRequest("A position is required to seek"))?;
+            let track = match playback.track_uuid {
+                Some(x) => Track::get(data.dal.clone(), x)?,
+                None => None
+            };
+            let track = track.ok_or(Error::Conflict("No track is playing".to_string()))?;
+            if position < 0 || position > track.duration * 1000 {
+                return Err(Error::BadRequest("Position must lie within the track"));
+            }
+
+            playback.position = position;
+            playback.reference_time = match playback.status {
+                PlaybackStatus::Playing => playback::start_time(&data),
+                _ => now,
+            };
+        },
+        PlaybackAction::Next => playback::advance(&data, &room, &mut playback).await?,
+    }
+
+    Ok(Payload(playback::save(&data, &playback)?))
+}
@@ -0,0 +1,18 @@
+use actix_web::web;
+use crate::appdata::WebData;
+use dal::{Room, uuid::Uuid, Dal, RoomExt};
+use proto::PlaybackStateResponse;
+use crate::error::{Error, WebResult};
+use crate::playback::to_proto;
+use actix_multiresponse::Payload;
+use tracing::instrument;
+
+#[instrument]
+pub async fn get(data: WebData, path: web::Path<Uuid>) -> WebResult<Payload<PlaybackStateResponse>> {
+    let room = match Room::get(data.dal.clone(), path.into_inner())? {
+        Some(x) => x,
+        None => return Err(Error::NotFound("The requested room does not exist"))
+    };
+
+    Ok(Payload(to_proto(&room.get_playback()?)))
+}
@@ -0,0 +1,3 @@
+pub mod control;
+pub mod get;
+pub mod time;
\ No newline at end of file
@@ -0,0 +1,35 @@
+use actix_web::web;
+use serde::Deserialize;
+use proto::PlaybackTimeResponse;
+use crate::error::WebResult;
+use crate::playback::now_millis;
+use actix_multiresponse::Payload;
+
+#[derive(Deserialize, Debug)]
+pub struct Query {
+    /// UNIX timestamp in milliseconds at which the client sent the request, client time
+    t0: i64,
+}
+
+pub async fn time(query: web::Query<Query>) -> WebResult<Payload<PlaybackTimeResponse>> {
+    let server_receive_time = now_millis();
+
+    Ok(Payload(PlaybackTimeResponse {
+        client_send_time: query.t0,
+        server_receive_time,
+        server_transmit_time: now_millis(),
+    }))
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn time_echoes_client_send_time() {
+        let response = time(web::Query(Query { t0: 1_234 })).await.unwrap().0;
+        assert_eq!(response.client_send_time, 1_234);
+        assert!(response.server_receive_time <= response.server_transmit_time);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,35 @@
+use dal::{Dal, PlayerDevice, Room};
+use dal::uuid::Uuid;
+use proto::{PlayerHeartbeatRequest, PlayerHeartbeatResponse};
+use crate::appdata::WebData;
+use crate::error::{Error, WebResult};
+use crate::player;
+use actix_multiresponse::Payload;
+use tracing::instrument;
+
+#[instrument]
+pub async fn heartbeat(data: WebData, payload: Payload<PlayerHeartbeatRequest>) -> WebResult<Payload<PlayerHeartbeatResponse>> {
+    let mut device = match PlayerDevice::get(data.dal.clone(), Uuid::parse_str(&payload.device_uuid)?)? {
+        Some(x) => x,
+        None => return Err(Error::NotFound("The requested player device does not exist"))
+    };
+
+    device.last_heartbeat = time::OffsetDateTime::now_utc().unix_timestamp();
+    device.update()?;
+
+    // A device which was replaced, or which was the only one left, takes over if nobody is playing
+    if !device.active {
+        let room = match Room::get(data.dal.clone(), device.room_uuid)? {
+            Some(x) => x,
+            None => return Err(Error::NotFound("The Room of the player device no longer exists"))
+        };
+
+        if player::get_active(&room)?.is_none() {
+            player::activate(&data, &mut device)?;
+        }
+    }
+
+    Ok(Payload(PlayerHeartbeatResponse {
+        active: device.active
+    }))
+}
@@ -0,0 +1,3 @@
+pub mod register;
+pub mod heartbeat;
+pub mod unregister;
\ No newline at end of file
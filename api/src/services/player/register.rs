@@ -0,0 +1,47 @@
+use dal::{Dal, PlayerDevice, PlayerDeviceBuildable, Room, RoomExt, User};
+use dal::uuid::Uuid;
+use proto::{PlayerRegisterRequest, PlayerRegisterResponse};
+use crate::appdata::WebData;
+use crate::error::{Error, WebResult};
+use crate::player;
+use actix_multiresponse::Payload;
+use tracing::instrument;
+
+#[instrument]
+pub async fn register(data: WebData, payload: Payload<PlayerRegisterRequest>) -> WebResult<Payload<PlayerRegisterResponse>> {
+    if payload.name.len() > 64 {
+        return Err(Error::BadRequest("Device name may not be longer than 64 characters"));
+    }
+
+    let room = match Room::get(data.dal.clone(), Uuid::parse_str(&payload.room_uuid)?)? {
+        Some(x) => x,
+        None => return Err(Error::NotFound("The requested Room does not exist"))
+    };
+
+    let user = match User::get(data.dal.clone(), Uuid::parse_str(&payload.user_uuid)?)? {
+        Some(x) => x,
+        None => return Err(Error::NotFound("The requested User does not exist"))
+    };
+
+    if room.list_members()?
+        .iter()
+        .filter(|x| x.uuid.eq(&user.uuid))
+        .collect::<Vec<_>>()
+        .is_empty() {
+        return Err(Error::Forbidden("User is not in Room"));
+    }
+
+    let mut device = PlayerDevice::create(data.dal.clone(), PlayerDeviceBuildable {
+        room_uuid: room.uuid,
+        user_uuid: user.uuid,
+        name: payload.name.to_string(),
+    })?;
+
+    if player::get_active(&room)?.is_none() {
+        player::activate(&data, &mut device)?;
+    }
+
+    Ok(Payload(PlayerRegisterResponse {
+        device: Some(player::to_proto(&device))
+    }))
+}
@@ -0,0 +1,35 @@
+use dal::{Dal, PlayerDevice, Room};
+use dal::uuid::Uuid;
+use proto::{PlayerUnregisterRequest, PlayerActiveResponse};
+use crate::appdata::WebData;
+use crate::error::{Error, WebResult};
+use crate::player;
+use actix_multiresponse::Payload;
+use tracing::instrument;
+
+#[instrument]
+pub async fn unregister(data: WebData, payload: Payload<PlayerUnregisterRequest>) -> WebResult<Payload<PlayerActiveResponse>> {
+    let device = match PlayerDevice::get(data.dal.clone(), Uuid::parse_str(&payload.device_uuid)?)? {
+        Some(x) => x,
+        None => return Err(Error::NotFound("The requested player device does not exist"))
+    };
+
+    let room_uuid = device.room_uuid;
+    let was_active = device.active;
+    device.delete()?;
+
+    let room = match Room::get(data.dal.clone(), room_uuid)? {
+        Some(x) => x,
+        None => return Ok(Payload(PlayerActiveResponse {
+            device: None
+        }))
+    };
+
+    if was_active {
+        player::promote_standby(&data, &room)?;
+    }
+
+    Ok(Payload(PlayerActiveResponse {
+        device: player::get_active(&room)?.as_ref().map(player::to_proto)
+    }))
+}
@@ -0,0 +1,3 @@
+pub mod vapid_key;
+pub mod subscribe;
+pub mod unsubscribe;
\ No newline at end of file
@@ -0,0 +1,54 @@
+use dal::{Dal, PushSubscription, PushSubscriptionBuildable, PushSubscriptionExt, User, MAX_PUSH_ENDPOINT_LENGTH, MAX_PUSH_KEY_LENGTH};
+use dal::uuid::Uuid;
+use proto::{PushSubscribeRequest, PushSubscribeResponse};
+use crate::appdata::WebData;
+use crate::error::{Error, WebResult};
+use crate::push;
+use actix_multiresponse::Payload;
+use tracing::instrument;
+use url::Url;
+
+#[instrument]
+pub async fn subscribe(data: WebData, payload: Payload<PushSubscribeRequest>) -> WebResult<Payload<PushSubscribeResponse>> {
+    if data.web_push.is_none() {
+        return Err(Error::NotFound("Web Push is not enabled on this server"));
+    }
+
+    if payload.endpoint.len() > MAX_PUSH_ENDPOINT_LENGTH {
+        return Err(Error::BadRequest("Endpoint may not be longer than 1024 characters"));
+    }
+
+    match Url::parse(&payload.endpoint) {
+        Ok(x) if x.scheme().eq("https") => {},
+        _ => return Err(Error::BadRequest("Endpoint must be an https URL"))
+    }
+
+    if payload.p256dh.len() > MAX_PUSH_KEY_LENGTH || payload.auth.len() > MAX_PUSH_KEY_LENGTH {
+        return Err(Error::BadRequest("Keys may not be longer than 256 characters"));
+    }
+
+    let user = match User::get(data.dal.clone(), Uuid::parse_str(&payload.user_uuid)?)? {
+        Some(x) => x,
+        None => return Err(Error::NotFound("The requested User does not exist"))
+    };
+
+    // Browsers resubscribe with the same endpoint when their keys change
+    let subscription = match PushSubscription::get_by_endpoint(data.dal.clone(), user.uuid, &payload.endpoint)? {
+        Some(mut x) => {
+            x.p256dh = payload.p256dh.clone();
+            x.auth = payload.auth.clone();
+            x.update()?;
+            x
+        },
+        None => PushSubscription::create(data.dal.clone(), PushSubscriptionBuildable {
+            user_uuid: user.uuid,
+            endpoint: payload.endpoint.clone(),
+            p256dh: payload.p256dh.clone(),
+            auth: payload.auth.clone(),
+        })?
+    };
+
+    Ok(Payload(PushSubscribeResponse {
+        subscription: Some(push::to_proto(&subscription))
+    }))
+}
\ No newline at end of file
@@ -0,0 +1,26 @@
+use dal::{Dal, PushSubscription, PushSubscriptionExt};
+use dal::uuid::Uuid;
+use proto::{PushSubscriptionListResponse, PushUnsubscribeRequest};
+use crate::appdata::WebData;
+use crate::error::{Error, WebResult};
+use crate::push;
+use actix_multiresponse::Payload;
+use tracing::instrument;
+
+#[instrument]
+pub async fn unsubscribe(data: WebData, payload: Payload<PushUnsubscribeRequest>) -> WebResult<Payload<PushSubscriptionListResponse>> {
+    let user_uuid = Uuid::parse_str(&payload.user_uuid)?;
+    match PushSubscription::get_by_endpoint(data.dal.clone(), user_uuid, &payload.endpoint)? {
+        Some(x) => x.delete()?,
+        None => return Err(Error::NotFound("The requested push subscription does not exist"))
+    }
+
+    let subscriptions = PushSubscription::list(data.dal.clone(), user_uuid)?
+        .iter()
+        .map(push::to_proto)
+        .collect::<Vec<_>>();
+
+    Ok(Payload(PushSubscriptionListResponse {
+        subscriptions
+    }))
+}
\ No newline at end of file
@@ -0,0 +1,15 @@
+use proto::PushVapidKeyResponse;
+use crate::appdata::WebData;
+use crate::error::{Error, WebResult};
+use actix_multiresponse::Payload;
+
+pub async fn vapid_key(data: WebData) -> WebResult<Payload<PushVapidKeyResponse>> {
+    let public_key = match (&data.web_push, &data.config.vapid_public_key) {
+        (Some(_), Some(x)) => x.clone(),
+        _ => return Err(Error::NotFound("Web Push is not enabled on this server"))
+    };
+
+    Ok(Payload(PushVapidKeyResponse {
+        public_key
+    }))
+}
\ No newline at end of file
@@ -0,0 +1,29 @@
+use dal::{Dal, Room, RoomExt, uuid::Uuid};
+use proto::{RoomCoHostRequest, RoomCoHostResponse};
+use crate::appdata::WebData;
+use crate::error::{Error, WebResult};
+use actix_multiresponse::Payload;
+use tracing::instrument;
+
+#[instrument]
+pub async fn co_host(data: WebData, payload: Payload<RoomCoHostRequest>) -> WebResult<Payload<RoomCoHostResponse>> {
+    let room = match Room::get(data.dal.clone(), Uuid::parse_str(&payload.room_uuid)?)? {
+        Some(x) => x,
+        None => return Err(Error::NotFound("The requested room does not exist"))
+    };
+
+    if room.owner.ne(&Uuid::parse_str(&payload.user_uuid)?) {
+        return Err(Error::Forbidden("Only the owner of the Room may appoint co-hosts"));
+    }
+
+    let member_uuid = Uuid::parse_str(&payload.member_uuid)?;
+    if room.list_members()?.iter().all(|x| x.uuid.ne(&member_uuid)) {
+        return Err(Error::NotFound("The requested member is not in the Room"));
+    }
+
+    room.set_co_host(&member_uuid, payload.co_host)?;
+
+    Ok(Payload(RoomCoHostResponse {
+        co_host: payload.co_host
+    }))
+}
@@ -15,19 +15,27 @@ pub async fn create(data: WebData, payload: Payload<RoomCreateRequest>) -> WebRe
         return Err(Error::BadRequest("Room name may not be longer than 64 characters"));
     }
 
+    let region_code = match &payload.region_code {
+        Some(x) if x.len() == 2 && x.chars().all(|c| c.is_ascii_alphabetic()) => Some(x.to_uppercase()),
+        Some(_) => return Err(Error::BadRequest("Region code must be an ISO 3166-1 alpha-2 code")),
+        None => None,
+    };
+
     let user = User::create(data.dal.clone(), UserBuildable {
         name: payload.user_name.to_string()
     })?;
 
     let mut room = Room::create(data.dal.clone(), RoomBuildable {
         name: payload.room_name.to_string(),
-        user_owner: user.uuid.clone()
+        user_owner: user.uuid.clone(),
+        region_code,
     })?;
     room.add_user(&user.uuid)?;
 
     Ok(Payload(RoomCreateResponse {
         room_uuid: room.uuid.to_string(),
         owner_uuid: user.uuid.to_string(),
-        join_code: room.join_code
+        join_code: room.join_code,
+        owner_secret: user.secret.unwrap_or_default(),
     }))
 }
\ No newline at end of file
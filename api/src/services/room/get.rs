@@ -3,6 +3,7 @@ use crate::appdata::WebData;
 use dal::{Room, uuid::Uuid, Dal, User};
 use proto::RoomInfoResponse;
 use crate::error::{Error, WebResult};
+use crate::player;
 use actix_multiresponse::Payload;
 use tracing::instrument;
 
@@ -18,11 +19,19 @@ pub async fn get(data: WebData, path: web::Path<Uuid>) -> WebResult<Payload<Room
         None => return Err(Error::Conflict("The room's owner does not exist".to_string()))
     };
 
+    let active_player = player::get_active(&room)?.as_ref().map(player::to_proto);
+
     Ok(Payload(RoomInfoResponse {
         room_uuid: room.uuid.to_string(),
         owner_uuid: room.owner.to_string(),
         join_code: room.join_code.to_string(),
         room_name: room.name,
-        owner_name: owner.name
+        owner_name: owner.name,
+        region_code: room.region_code,
+        autoplay: room.autoplay,
+        active_player,
+        require_approval: room.require_approval,
+        max_pending_per_user: room.max_pending_per_user.unwrap_or(0),
+        add_cooldown: room.add_cooldown.unwrap_or(0),
     }))
 }
\ No newline at end of file
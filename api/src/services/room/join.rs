@@ -1,4 +1,5 @@
 use dal::{Room, RoomExt, Dal, User, UserBuildable};
+use dal::uuid::Uuid;
 use proto::{RoomJoinRequest, RoomJoinResponse};
 use crate::appdata::WebData;
 use crate::error::{Error, WebResult};
@@ -7,22 +8,42 @@ use tracing::instrument;
 
 #[instrument]
 pub async fn join(data: WebData, payload: Payload<RoomJoinRequest>) -> WebResult<Payload<RoomJoinResponse>> {
-    if payload.user_name.len() > 64 {
-        return Err(Error::BadRequest("User name may not be longer than 64 characters"));
-    }
-
     let mut room = match Room::get_by_join_code(data.dal.clone(), &payload.room_join_code)? {
         Some(x) => x,
         None => return Err(Error::NotFound("The requested room does not exist"))
     };
 
-    let user = User::create(data.dal.clone(), UserBuildable {
-        name: payload.user_name.clone()
-    })?;
-    room.add_user(&user.uuid)?;
+    let user = match (&payload.user_uuid, &payload.user_secret) {
+        (Some(user_uuid), Some(user_secret)) => {
+            let user = match User::get(data.dal.clone(), Uuid::parse_str(user_uuid)?)? {
+                Some(x) => x,
+                None => return Err(Error::NotFound("The requested user does not exist"))
+            };
+
+            if !user.verify_secret(user_secret) {
+                return Err(Error::Unauthorized("The provided user secret is invalid"));
+            }
+
+            user
+        },
+        (None, None) => {
+            if payload.user_name.len() > 64 {
+                return Err(Error::BadRequest("User name may not be longer than 64 characters"));
+            }
+
+            User::create(data.dal.clone(), UserBuildable {
+                name: payload.user_name.clone()
+            })?
+        },
+        _ => return Err(Error::BadRequest("User UUID and user secret must be provided together"))
+    };
+
+    let rejoined = !room.add_user(&user.uuid)?;
 
     Ok(Payload(RoomJoinResponse {
         room_uuid: room.uuid.to_string(),
         user_uuid: user.uuid.to_string(),
+        user_secret: user.secret.unwrap_or_default(),
+        rejoined,
     }))
 }
\ No newline at end of file
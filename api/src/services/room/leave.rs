@@ -1,7 +1,8 @@
 use dal::{Dal, RemoveStatus, Room, RoomExt, uuid::Uuid};
-use proto::{RoomLeaveRequest, RoomLeaveResponse};
+use proto::{RoomLeaveRequest, RoomLeaveResponse, UserNotification, UserNotificationKind};
 use crate::appdata::WebData;
 use crate::error::{Error, WebResult};
+use crate::notify;
 use actix_multiresponse::Payload;
 use tracing::instrument;
 
@@ -12,6 +13,7 @@ pub async fn leave(data: WebData, payload: Payload<RoomLeaveRequest>) -> WebResu
         None => return Err(Error::NotFound("The requested room does not exist"))
     };
 
+    let previous_owner = room.owner;
     let resp = match room.remove_user(&Uuid::parse_str(&payload.user_uuid)?)? {
         RemoveStatus::LastMember => {
             room.delete()?;
@@ -21,6 +23,15 @@ pub async fn leave(data: WebData, payload: Payload<RoomLeaveRequest>) -> WebResu
             }
         },
         RemoveStatus::Ok { new_owner} =>  {
+            if new_owner.ne(&previous_owner) {
+                notify::send(&data, new_owner, UserNotification {
+                    kind: UserNotificationKind::NowOwner.into(),
+                    room_uuid: room.uuid.to_string(),
+                    track: None,
+                    reason: None,
+                });
+            }
+
             RoomLeaveResponse {
                 deleted: false,
                 new_owner: Some(new_owner.to_string())
@@ -23,7 +23,8 @@ pub async fn members(data: WebData, path: web::Path<Uuid>) -> WebResult<Payload<
                 uuid: m.uuid.to_string(),
                 name: user.name,
                 owner: room.owner.eq(&user.uuid),
-                joined_at: m.joined_at
+                joined_at: m.joined_at,
+                co_host: m.co_host,
             })
         })
         .collect::<Result<Vec<_>, Error>>()?;
@@ -2,4 +2,6 @@ pub mod create;
 pub mod get;
 pub mod join;
 pub mod leave;
-pub mod members;
\ No newline at end of file
+pub mod members;
+pub mod settings;
+pub mod co_host;
\ No newline at end of file
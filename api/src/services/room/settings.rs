@@ -0,0 +1,70 @@
+use dal::{Dal, Room, SkipThreshold, uuid::Uuid};
+use proto::{RoomSettingsRequest, RoomSettingsResponse, SkipThresholdKind};
+use crate::appdata::WebData;
+use crate::error::{Error, WebResult};
+use actix_multiresponse::Payload;
+use tracing::instrument;
+
+#[instrument]
+pub async fn settings(data: WebData, payload: Payload<RoomSettingsRequest>) -> WebResult<Payload<RoomSettingsResponse>> {
+    let mut room = match Room::get(data.dal.clone(), Uuid::parse_str(&payload.room_uuid)?)? {
+        Some(x) => x,
+        None => return Err(Error::NotFound("The requested room does not exist"))
+    };
+
+    if room.owner.ne(&Uuid::parse_str(&payload.user_uuid)?) {
+        return Err(Error::Forbidden("Only the owner of the Room may change its settings"));
+    }
+
+    if let Some(autoplay) = payload.autoplay {
+        room.autoplay = autoplay;
+    }
+
+    if let Some(skip_threshold) = &payload.skip_threshold {
+        room.skip_threshold = skip_threshold_from_proto(skip_threshold)?;
+    }
+
+    if let Some(require_approval) = payload.require_approval {
+        room.require_approval = require_approval;
+    }
+
+    if let Some(max_pending_per_user) = payload.max_pending_per_user {
+        room.max_pending_per_user = (max_pending_per_user > 0).then(|| max_pending_per_user);
+    }
+
+    if let Some(add_cooldown) = payload.add_cooldown {
+        room.add_cooldown = (add_cooldown > 0).then(|| add_cooldown);
+    }
+
+    room.update()?;
+
+    Ok(Payload(RoomSettingsResponse {
+        autoplay: room.autoplay,
+        skip_threshold: Some(skip_threshold_to_proto(room.skip_threshold)),
+        require_approval: room.require_approval,
+        max_pending_per_user: room.max_pending_per_user.unwrap_or(0),
+        add_cooldown: room.add_cooldown.unwrap_or(0),
+    }))
+}
+
+fn skip_threshold_from_proto(threshold: &proto::SkipThreshold) -> WebResult<SkipThreshold> {
+    match SkipThresholdKind::from_i32(threshold.kind) {
+        Some(SkipThresholdKind::Percentage) if (1..=100).contains(&threshold.value) => Ok(SkipThreshold::Percentage(threshold.value)),
+        Some(SkipThresholdKind::Percentage) => Err(Error::BadRequest("Skip threshold percentage must be between 1 and 100")),
+        Some(SkipThresholdKind::Absolute) if threshold.value >= 1 => Ok(SkipThreshold::Absolute(threshold.value)),
+        Some(SkipThresholdKind::Absolute) => Err(Error::BadRequest("Skip threshold must be at least 1 vote")),
+        None => Err(Error::BadRequest("Unknown skip threshold kind")),
+    }
+}
+
+fn skip_threshold_to_proto(threshold: SkipThreshold) -> proto::SkipThreshold {
+    let (kind, value) = match threshold {
+        SkipThreshold::Percentage(x) => (SkipThresholdKind::Percentage, x),
+        SkipThreshold::Absolute(x) => (SkipThresholdKind::Absolute, x),
+    };
+
+    proto::SkipThreshold {
+        kind: kind.into(),
+        value,
+    }
+}
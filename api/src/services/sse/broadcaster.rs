@@ -160,6 +160,11 @@ impl Broadcaster {
         })
     }
 
+    /// Whether any client is still connected
+    pub fn has_clients(&self) -> bool {
+        self.clients.iter().any(|x| !x.sender.is_closed())
+    }
+
     #[allow(unused)]
     // TODO remove unused
     pub fn send<D: Serialize + Message + Clone>(&self, event: SsePacketEvent, data: D) -> Result<(), SseError> {
@@ -0,0 +1,22 @@
+use proto::{CacheStatistics, CacheStatusResponse};
+use crate::appdata::WebData;
+use crate::cache::TieredCache;
+use crate::error::WebResult;
+use actix_multiresponse::Payload;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+pub async fn cache(data: WebData) -> WebResult<Payload<CacheStatusResponse>> {
+    Ok(Payload(CacheStatusResponse {
+        youtube_videos: Some(statistics(data.youtube_cache.videos())),
+        youtube_searches: Some(statistics(data.youtube_cache.searches())),
+    }))
+}
+
+fn statistics<T: Clone + Serialize + DeserializeOwned>(cache: &TieredCache<T>) -> CacheStatistics {
+    CacheStatistics {
+        hits: cache.counter().hits(),
+        misses: cache.counter().misses(),
+        size: cache.size() as u64,
+    }
+}
@@ -0,0 +1,27 @@
+use proto::{QuotaStatusResponse, QuotaUsage};
+use crate::appdata::WebData;
+use crate::error::WebResult;
+use actix_multiresponse::Payload;
+
+pub async fn quota(data: WebData) -> WebResult<Payload<QuotaStatusResponse>> {
+    let status = data.youtube_quota.status();
+    let remaining = status.remaining();
+
+    let mut usage = status.usage.into_iter()
+        .map(|(call, usage)| QuotaUsage {
+            call: call.name().to_string(),
+            calls: usage.calls,
+            units: usage.units,
+        })
+        .collect::<Vec<_>>();
+    usage.sort_by(|a, b| a.call.cmp(&b.call));
+
+    Ok(Payload(QuotaStatusResponse {
+        usage,
+        used: status.used,
+        remaining,
+        daily_quota: status.daily_quota,
+        exhausted: status.exhausted,
+        resets_at: status.resets_at,
+    }))
+}
@@ -1,8 +1,12 @@
-use dal::{Dal, Pretrack, Room, RoomExt, Track, TrackBuildable, User};
+use dal::{AddLimits, Dal, EnqueuePretrackStatus, Pretrack, QueueStatus, Room, RoomExt, User};
 use dal::uuid::Uuid;
 use proto::{SsePacketEvent, TrackAddRequest, TrackAddResponse};
 use crate::appdata::WebData;
 use crate::error::{Error, WebResult};
+use crate::blocklist::Blocklist;
+use crate::notify;
+use crate::playback;
+use crate::services::tracks::list::track_to_proto;
 use actix_multiresponse::Payload;
 use tracing::instrument;
 
@@ -31,22 +35,30 @@ pub async fn add(data: WebData, payload: Payload<TrackAddRequest>) -> WebResult<
         None => return Err(Error::NotFound("The requested Pretrack does not exist"))
     };
 
-    let track = Track::create(data.dal.clone(), TrackBuildable {
-        room_uuid: room.uuid,
-        pretrack
-    })?;
+    if pretrack.room_uuid.ne(&room.uuid) || pretrack.user_uuid.ne(&user.uuid) {
+        return Err(Error::Forbidden("The requested Pretrack was created in another Room or by another User"));
+    }
+
+    // The blocklist may have changed since the Pretrack was looked up
+    Blocklist::for_room(&data.dal, room.uuid)?.check(&pretrack.platform.get_track_id(), &pretrack.artist, &pretrack.name)?;
 
-    let track_idx = room.get_queue()?.enqueue(&track, &user.uuid)?;
+    // Moderators are not subject to approval or limits
+    let is_moderator = room.is_moderator(&user.uuid)?;
+    let pending = room.require_approval && !is_moderator;
+    let limits = if is_moderator { AddLimits::default() } else { room.add_limits() };
 
-    let proto_track = proto::Track {
-        track_uuid: track.uuid.to_string(),
-        track_name: track.name,
-        artist_name: track.artist,
-        track_duration: track.duration,
-        thumbnail_url: track.thumbnail_url,
-        track_idx
+    // The Pretrack is kept if the limits are exceeded, so it can still be added once allowed
+    let status = if pending { QueueStatus::Pending } else { QueueStatus::Enqueued };
+    let track = match room.get_queue()?.enqueue_pretrack(pretrack, &user.uuid, status, limits)? {
+        EnqueuePretrackStatus::Ok { track, .. } => track,
+        EnqueuePretrackStatus::LimitExceeded(exceeded) => return Err(exceeded.into())
     };
 
+    notify::track_up_next_if_next(&data, &room, &track);
+
+    let mut proto_track = track_to_proto(&data.dal, &track)?;
+    proto_track.estimated_start = playback::schedule(&room)?.start_times.get(&track.uuid).copied();
+
     let sse = data.sse_track_list.lock();
     if let Some(broadcaster) = sse.get(&room.uuid) {
         let lock = broadcaster.lock();
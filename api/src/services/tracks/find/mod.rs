@@ -1,2 +1,3 @@
 pub mod youtube;
 pub mod search;
+pub mod url;
@@ -1,42 +1,149 @@
 use actix_web::web;
 use crate::appdata::WebData;
 use serde::Deserialize;
-use tracing::instrument;
+use tracing::{debug, instrument, warn};
+use dal::{Dal, Room};
+use dal::uuid::Uuid;
 use proto::TrackFindSearchResponse;
-use crate::apis::youtube::YouTubeApi;
-use crate::error::WebResult;
+use crate::apis::youtube::{self, VideoResource};
+use crate::error::{Error, WebResult};
+use crate::blocklist::Blocklist;
 use actix_multiresponse::Payload;
 
+/// The amount of results per provider if no limit is provided
+const DEFAULT_LIMIT: u32 = 5;
+/// The maximum amount of results per provider
+const MAX_LIMIT: u32 = 50;
+
 #[derive(Deserialize, Debug)]
 pub struct Query {
     q: String,
+    /// If provided, results which can not be played in the Room's region are omitted
+    room_uuid: Option<Uuid>,
+    /// The maximum amount of results per provider
+    limit: Option<u32>,
+    /// The `next_page_token` of the previous page
+    page_token: Option<String>,
+}
+
+/// The page tokens of every provider, combined into the single page token handed to clients.
+/// Encoded as `<provider>.<token>` pairs separated by `~`.
+/// A provider without a token has no further results
+#[derive(Debug, Default)]
+struct PageToken {
+    youtube: Option<String>,
+}
+
+impl PageToken {
+    fn parse(token: &str) -> Option<Self> {
+        let mut this = Self::default();
+        for part in token.split('~').filter(|x| !x.is_empty()) {
+            let (provider, token) = part.split_once('.')?;
+            match provider {
+                "youtube" => this.youtube = Some(token.to_string()),
+                _ => return None,
+            }
+        }
+
+        Some(this)
+    }
+
+    /// Returns `None` if no provider has further results
+    fn encode(&self) -> Option<String> {
+        let parts = [("youtube", &self.youtube)].into_iter()
+            .filter_map(|(provider, token)| token.as_ref().map(|token| format!("{provider}.{token}")))
+            .collect::<Vec<_>>();
+
+        (!parts.is_empty()).then(|| parts.join("~"))
+    }
+}
+
+/// The page of results to request from a provider
+#[derive(Debug)]
+enum ProviderPage {
+    First,
+    Next(String),
+    Exhausted,
+}
+
+impl ProviderPage {
+    fn new(page_token: Option<&PageToken>, provider_token: impl FnOnce(&PageToken) -> Option<String>) -> Self {
+        match page_token {
+            None => Self::First,
+            Some(x) => match provider_token(x) {
+                Some(x) => Self::Next(x),
+                None => Self::Exhausted,
+            }
+        }
+    }
+
+    fn token(&self) -> Option<&str> {
+        match self {
+            Self::Next(x) => Some(x.as_str()),
+            _ => None,
+        }
+    }
 }
 
 #[instrument]
 pub async fn search(data: WebData, query: web::Query<Query>) -> WebResult<Payload<TrackFindSearchResponse>> {
+    let region_code = match query.room_uuid {
+        Some(room_uuid) => match Room::get(data.dal.clone(), room_uuid)? {
+            Some(x) => x.region_code,
+            None => return Err(Error::NotFound("The requested Room does not exist"))
+        },
+        None => None
+    };
+
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT);
+    if limit == 0 || limit > MAX_LIMIT {
+        return Err(Error::BadRequest("Limit must be between 1 and 50"));
+    }
+
+    let page_token = match &query.page_token {
+        Some(x) => Some(PageToken::parse(x).ok_or(Error::BadRequest("Invalid page token"))?),
+        None => None,
+    };
+    let blocklist = match query.room_uuid {
+        Some(room_uuid) => Blocklist::for_room(&data.dal, room_uuid)?,
+        None => Blocklist::global(&data.dal)?,
+    };
+
+    let youtube_page = ProviderPage::new(page_token.as_ref(), |x| x.youtube.clone());
 
     let youtube_result = tokio::spawn(async move {
-        search_youtube(YouTubeApi { token: data.config.google_api_key.clone() }, &query.q).await
+        match youtube_page {
+            ProviderPage::Exhausted => Ok((Vec::new(), None)),
+            _ => search_youtube(data, &query.q, limit, youtube_page.token(), region_code.as_deref(), &blocklist).await,
+        }
     });
 
     let youtube_result = tokio::join! {
         youtube_result
     };
 
-    let youtube_result = youtube_result.0??;
+    let (youtube_tracks, youtube_token) = youtube_result.0??;
+    let next_page_token = PageToken {
+        youtube: youtube_token,
+    }.encode();
+
     Ok(Payload(TrackFindSearchResponse {
-        tracks: youtube_result
+        tracks: youtube_tracks,
+        next_page_token,
     }))
 }
 
-#[instrument]
-async fn search_youtube(youtube_api: YouTubeApi, query: &str) -> WebResult<Vec<proto::TrackFindSearchTrack>> {
-    let search = youtube_api.search(query).await?;
-    let handles = search.into_iter()
-        .map(|x| {
+/// Search YouTube. Returns the tracks found and the token of the next page, if any
+#[instrument(skip(blocklist))]
+async fn search_youtube(data: WebData, query: &str, limit: u32, page_token: Option<&str>, region_code: Option<&str>, blocklist: &Blocklist) -> WebResult<(Vec<proto::TrackFindSearchTrack>, Option<String>)> {
+    let youtube_api = data.youtube_api();
+    let results = data.youtube_cache.search(&youtube_api, query, limit, page_token).await?;
+    let handles = results.video_ids.into_iter()
+        .map(|video_id| {
             let api = youtube_api.clone();
+            let data = data.clone();
             tokio::spawn(async move {
-                api.clone().get_video(x.id.video_id).await
+                data.youtube_cache.get_video(&api, &video_id).await
             })
         })
         .collect::<Vec<_>>();
@@ -45,16 +152,42 @@ async fn search_youtube(youtube_api: YouTubeApi, query: &str) -> WebResult<Vec<p
         .filter_map(|x| x.ok())
         .filter_map(|x| x.ok())
         .filter_map(|x| x)
-        .map(|x| {
-            proto::TrackFindSearchTrack {
-                thumbnail_url: x.snippet.get_best_thumbnail(),
-                name: x.snippet.get_title(),
-                duration: x.content_details.duration(),
-                youtube_id: Some(x.id),
-                spotify_id: None,
-                artist: x.snippet.channel_title,
+        .filter(|x| match x.check_playable(region_code) {
+            Ok(_) => true,
+            Err(reason) => {
+                debug!("Omitting YouTube video {} from search results: {reason}", x.id);
+                false
+            }
+        })
+        .filter_map(|x| {
+            let video_id = x.id.clone();
+            match youtube_search_track(x) {
+                Ok(x) => Some(x),
+                Err(e) => {
+                    warn!("Skipping YouTube video {video_id} in search results: {e}");
+                    None
+                }
+            }
+        })
+        .filter(|x| match blocklist.check(x.youtube_id.as_deref().unwrap_or_default(), &x.artist, &x.name) {
+            Ok(_) => true,
+            Err(reason) => {
+                debug!("Omitting blocked YouTube video {:?} from search results: {reason}", x.youtube_id);
+                false
             }
         })
         .collect::<Vec<_>>();
-    Ok(tracks)
+    Ok((tracks, results.next_page_token))
+}
+
+fn youtube_search_track(video: VideoResource) -> youtube::Result<proto::TrackFindSearchTrack> {
+    let metadata = video.snippet.get_metadata();
+    Ok(proto::TrackFindSearchTrack {
+        thumbnail_url: video.snippet.get_best_thumbnail()?,
+        name: metadata.name,
+        duration: video.content_details.duration()?,
+        youtube_id: Some(video.id),
+        spotify_id: None,
+        artist: metadata.artist,
+    })
 }
\ No newline at end of file
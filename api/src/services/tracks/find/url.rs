@@ -0,0 +1,223 @@
+use proto::{TrackFindUrlRequest, TrackFindUrlResponse, TrackPlatform};
+use crate::appdata::WebData;
+use crate::error::{Error, WebResult};
+use actix_multiresponse::Payload;
+use dal::{User, Room, Dal};
+use dal::uuid::Uuid;
+use tracing::instrument;
+use url::Url;
+use crate::services::tracks::find::youtube::create_youtube_pretrack;
+
+const YOUTUBE_ID_LENGTH: usize = 11;
+const SPOTIFY_ID_LENGTH: usize = 22;
+
+/// A track on a supported platform, as identified from a user supplied URL
+#[derive(Debug, PartialEq, Eq)]
+pub enum PlatformUrl {
+    YouTube(String),
+    Spotify(String),
+}
+
+#[instrument]
+pub async fn url(data: WebData, payload: Payload<TrackFindUrlRequest>) -> WebResult<Payload<TrackFindUrlResponse>> {
+    let room = match Room::get(data.dal.clone(), Uuid::parse_str(&payload.room_uuid)?)? {
+        Some(x) => x,
+        None => return Err(Error::NotFound("The requested Room does not exist"))
+    };
+
+    let user = match User::get(data.dal.clone(), Uuid::parse_str(&payload.user_uuid)?)? {
+        Some(x) => x,
+        None => return Err(Error::NotFound("The requested User does not exist"))
+    };
+
+    let (pretrack, platform) = match parse_platform_url(&payload.url) {
+        Some(PlatformUrl::YouTube(id)) => (create_youtube_pretrack(&data, &id, &room, user.uuid).await?, TrackPlatform::YouTube),
+        Some(PlatformUrl::Spotify(_)) => return Err(Error::BadRequest("Spotify tracks are not supported yet")),
+        None => return Err(Error::BadRequest("The provided URL is not a supported track URL")),
+    };
+
+    Ok(Payload(TrackFindUrlResponse {
+        pretrack_uuid: pretrack.uuid.to_string(),
+        platform: platform.into(),
+    }))
+}
+
+/// Parse a YouTube or Spotify URL, as copied from a browser or share sheet, into a platform track ID.
+/// The scheme may be omitted, e.g. `youtu.be/dQw4w9WgXcQ` is accepted. Spotify URIs like `spotify:track:<id>` are accepted as well.
+pub fn parse_platform_url(input: &str) -> Option<PlatformUrl> {
+    let input = input.trim();
+
+    if let Some(id) = input.strip_prefix("spotify:track:") {
+        return is_spotify_id(id).then(|| PlatformUrl::Spotify(id.to_string()));
+    }
+
+    let url = parse_lenient(input)?;
+    let host = url.host_str()?.to_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+    let segments = url.path_segments()
+        .map(|x| x.filter(|x| !x.is_empty()).collect::<Vec<_>>())
+        .unwrap_or_default();
+
+    match host {
+        "youtu.be" => segments.first()
+            .filter(|x| is_youtube_id(x))
+            .map(|x| PlatformUrl::YouTube(x.to_string())),
+        "youtube.com" | "m.youtube.com" | "music.youtube.com" | "youtube-nocookie.com" => {
+            let id = match segments.as_slice() {
+                ["watch"] => url.query_pairs()
+                    .find(|(k, _)| k.eq("v"))
+                    .map(|(_, v)| v.to_string()),
+                ["shorts" | "embed" | "live" | "v", id, ..] => Some(id.to_string()),
+                _ => None,
+            }?;
+
+            is_youtube_id(&id).then(|| PlatformUrl::YouTube(id))
+        },
+        "open.spotify.com" | "play.spotify.com" => {
+            // Localized links look like /intl-nl/track/<id>
+            let segments = match segments.first() {
+                Some(x) if x.starts_with("intl-") => &segments[1..],
+                _ => &segments[..],
+            };
+
+            match segments {
+                ["track", id, ..] if is_spotify_id(id) => Some(PlatformUrl::Spotify(id.to_string())),
+                _ => None,
+            }
+        },
+        _ => None,
+    }
+}
+
+/// Get the playlist ID from a YouTube playlist URL, e.g. `youtube.com/playlist?list=<id>` or a
+/// `watch` URL with a `list` parameter. Input which is not a URL is assumed to be a bare playlist ID
+pub fn parse_playlist_id(input: &str) -> Option<String> {
+    let input = input.trim();
+    let is_playlist_id = |id: &str| !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+    if !input.contains('/') {
+        return is_playlist_id(input).then(|| input.to_string());
+    }
+
+    let url = parse_lenient(input)?;
+    url.query_pairs()
+        .find(|(k, _)| k.eq("list"))
+        .map(|(_, v)| v.to_string())
+        .filter(|x| is_playlist_id(x.as_str()))
+}
+
+/// Parse a URL, assuming `https` if no scheme is present
+fn parse_lenient(input: &str) -> Option<Url> {
+    if input.contains("://") {
+        Url::parse(input).ok()
+    } else {
+        Url::parse(&format!("https://{input}")).ok()
+    }
+}
+
+fn is_youtube_id(id: &str) -> bool {
+    id.len() == YOUTUBE_ID_LENGTH && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn is_spotify_id(id: &str) -> bool {
+    id.len() == SPOTIFY_ID_LENGTH && id.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const YT: &str = "dQw4w9WgXcQ";
+    const SP: &str = "4uLU6hMCjMI75M1A2tKUQC";
+
+    fn youtube() -> Option<PlatformUrl> {
+        Some(PlatformUrl::YouTube(YT.to_string()))
+    }
+
+    fn spotify() -> Option<PlatformUrl> {
+        Some(PlatformUrl::Spotify(SP.to_string()))
+    }
+
+    #[test]
+    fn platform_urls() {
+        let cases = [
+            // youtu.be
+            ("https://youtu.be/dQw4w9WgXcQ", youtube()),
+            ("https://youtu.be/dQw4w9WgXcQ?si=Qx3bT8zJk1aP0cWe", youtube()),
+            ("https://youtu.be/dQw4w9WgXcQ?t=42", youtube()),
+            ("youtu.be/dQw4w9WgXcQ", youtube()),
+            // youtube.com
+            ("https://www.youtube.com/watch?v=dQw4w9WgXcQ", youtube()),
+            ("https://www.youtube.com/watch?v=dQw4w9WgXcQ&list=PLFgquLnL59alCl_2TQvOiD5Vgm1hCaGSI&index=3", youtube()),
+            ("https://www.youtube.com/watch?feature=share&v=dQw4w9WgXcQ", youtube()),
+            ("http://youtube.com/watch?v=dQw4w9WgXcQ", youtube()),
+            ("www.youtube.com/watch?v=dQw4w9WgXcQ", youtube()),
+            ("youtube.com/watch?v=dQw4w9WgXcQ", youtube()),
+            ("HTTPS://WWW.YOUTUBE.COM/watch?v=dQw4w9WgXcQ", youtube()),
+            ("  https://www.youtube.com/watch?v=dQw4w9WgXcQ\n", youtube()),
+            ("https://m.youtube.com/watch?v=dQw4w9WgXcQ&feature=youtu.be", youtube()),
+            ("https://music.youtube.com/watch?v=dQw4w9WgXcQ&list=RDAMVMdQw4w9WgXcQ", youtube()),
+            ("music.youtube.com/watch?v=dQw4w9WgXcQ&feature=share", youtube()),
+            ("https://www.youtube.com/shorts/dQw4w9WgXcQ", youtube()),
+            ("https://youtube.com/shorts/dQw4w9WgXcQ?feature=share", youtube()),
+            ("https://www.youtube.com/embed/dQw4w9WgXcQ", youtube()),
+            ("https://www.youtube.com/embed/dQw4w9WgXcQ?autoplay=1", youtube()),
+            ("https://www.youtube-nocookie.com/embed/dQw4w9WgXcQ", youtube()),
+            ("https://www.youtube.com/live/dQw4w9WgXcQ?si=abc", youtube()),
+            ("https://www.youtube.com/v/dQw4w9WgXcQ", youtube()),
+            // Spotify
+            ("https://open.spotify.com/track/4uLU6hMCjMI75M1A2tKUQC", spotify()),
+            ("https://open.spotify.com/track/4uLU6hMCjMI75M1A2tKUQC?si=1a2b3c4d5e6f7a8b", spotify()),
+            ("https://open.spotify.com/intl-nl/track/4uLU6hMCjMI75M1A2tKUQC", spotify()),
+            ("https://open.spotify.com/intl-pt/track/4uLU6hMCjMI75M1A2tKUQC?si=abc", spotify()),
+            ("open.spotify.com/track/4uLU6hMCjMI75M1A2tKUQC", spotify()),
+            ("https://play.spotify.com/track/4uLU6hMCjMI75M1A2tKUQC", spotify()),
+            ("spotify:track:4uLU6hMCjMI75M1A2tKUQC", spotify()),
+            // Invalid
+            ("", None),
+            ("not a url", None),
+            ("https://youtu.be/", None),
+            ("https://youtu.be/dQw4w9WgXc", None),
+            ("https://youtu.be/dQw4w9WgXcQQ", None),
+            ("https://www.youtube.com/watch?v=dQw4w9WgXc", None),
+            ("https://www.youtube.com/watch?v=dQw4w9WgXc$", None),
+            ("https://www.youtube.com/watch", None),
+            ("https://www.youtube.com/playlist?list=PLFgquLnL59alCl_2TQvOiD5Vgm1hCaGSI", None),
+            ("https://www.youtube.com/channel/UCuAXFkgsw1L7xaCfnd5JJOw", None),
+            ("https://www.youtube.com/shorts/", None),
+            ("https://example.com/watch?v=dQw4w9WgXcQ", None),
+            ("https://open.spotify.com/album/4uLU6hMCjMI75M1A2tKUQC", None),
+            ("https://open.spotify.com/track/4uLU6hMCjMI75M1A2tKUQ", None),
+            ("https://open.spotify.com/track/4uLU6hMCjMI75M1A2tKUQC_", None),
+            ("spotify:track:4uLU6hMCjMI75M1A2tKU", None),
+            ("spotify:album:4uLU6hMCjMI75M1A2tKUQC", None),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(parse_platform_url(input), expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn playlist_ids() {
+        let id = "PLFgquLnL59alCl_2TQvOiD5Vgm1hCaGSI";
+        let cases = [
+            ("https://www.youtube.com/playlist?list=PLFgquLnL59alCl_2TQvOiD5Vgm1hCaGSI", Some(id)),
+            ("youtube.com/playlist?list=PLFgquLnL59alCl_2TQvOiD5Vgm1hCaGSI&si=abc", Some(id)),
+            ("https://www.youtube.com/watch?v=dQw4w9WgXcQ&list=PLFgquLnL59alCl_2TQvOiD5Vgm1hCaGSI", Some(id)),
+            ("https://music.youtube.com/playlist?list=PLFgquLnL59alCl_2TQvOiD5Vgm1hCaGSI", Some(id)),
+            ("PLFgquLnL59alCl_2TQvOiD5Vgm1hCaGSI", Some(id)),
+            (" PLFgquLnL59alCl_2TQvOiD5Vgm1hCaGSI ", Some(id)),
+            ("", None),
+            ("not a playlist", None),
+            ("https://www.youtube.com/watch?v=dQw4w9WgXcQ", None),
+            ("https://www.youtube.com/playlist?list=", None),
+            ("https://www.youtube.com/playlist?list=PL$invalid", None),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(parse_playlist_id(input).as_deref(), expected, "{input}");
+        }
+    }
+}
\ No newline at end of file
@@ -1,39 +1,60 @@
 use proto::{TrackFindYouTubeRequest, TrackFindYouTubeResponse};
-use crate::appdata::WebData;
+use crate::appdata::{AppData, WebData};
 use crate::error::{Error, WebResult};
 use actix_multiresponse::Payload;
-use dal::{User, Room, Dal, Pretrack, PretrackBuildable, PretrackSourcePlatform};
+use dal::{User, Room, Dal, Mysql, Pretrack, PretrackBuildable, PretrackSourcePlatform};
 use dal::uuid::Uuid;
-use crate::apis::youtube::YouTubeApi;
+use crate::apis::youtube::VideoResource;
+use crate::blocklist::Blocklist;
 use tracing::instrument;
 
 #[instrument]
 pub async fn youtube(data: WebData, payload: Payload<TrackFindYouTubeRequest>) -> WebResult<Payload<TrackFindYouTubeResponse>> {
-    match Room::get(data.dal.clone(), Uuid::parse_str(&payload.room_uuid)?)? {
+    let room = match Room::get(data.dal.clone(), Uuid::parse_str(&payload.room_uuid)?)? {
         Some(x) => x,
         None => return Err(Error::NotFound("The requested Room does not exist"))
     };
 
-    match User::get(data.dal.clone(), Uuid::parse_str(&payload.user_uuid)?)? {
+    let user = match User::get(data.dal.clone(), Uuid::parse_str(&payload.user_uuid)?)? {
         Some(x) => x,
         None => return Err(Error::NotFound("The requested User does not exist"))
     };
 
-    let api = YouTubeApi { token: data.config.google_api_key.clone() };
-    let video = match api.get_video(&payload.youtube_id).await? {
+    let pretrack = create_youtube_pretrack(&data, &payload.youtube_id, &room, user.uuid).await?;
+
+    Ok(Payload(TrackFindYouTubeResponse {
+        pretrack_uuid: pretrack.uuid.to_string()
+    }))
+}
+
+/// Look up a YouTube video and store it as a Pretrack, bound to the Room and User looking it up
+pub async fn create_youtube_pretrack(data: &AppData, youtube_id: &str, room: &Room<Mysql>, user_uuid: Uuid) -> WebResult<Pretrack<Mysql>> {
+    let api = data.youtube_api();
+    let video = match data.youtube_cache.get_video(&api, youtube_id).await? {
         Some(x) => x,
         None => return Err(Error::NotFound("The requested YouTube video does not exist")),
     };
 
+    create_pretrack_from_video(data, video, room, user_uuid)
+}
+
+/// Store an already retrieved YouTube video as a Pretrack, bound to the Room and User looking it up.
+/// Videos which can not be played in the Room, or which are blocked, are rejected
+pub fn create_pretrack_from_video(data: &AppData, video: VideoResource, room: &Room<Mysql>, user_uuid: Uuid) -> WebResult<Pretrack<Mysql>> {
+    video.check_playable(room.region_code.as_deref())?;
+
+    let metadata = video.snippet.get_metadata();
+    Blocklist::for_room(&data.dal, room.uuid)?.check(&video.id, &metadata.artist, &metadata.name)?;
+
     let pretrack = Pretrack::create(data.dal.clone(), PretrackBuildable {
-        duration: video.content_details.duration(),
-        thumbnail_url: video.snippet.get_best_thumbnail(),
-        name: video.snippet.get_title(),
-        artist: video.snippet.channel_title,
-        platform: PretrackSourcePlatform::YouTube(payload.youtube_id.clone())
+        duration: video.content_details.duration()?,
+        thumbnail_url: video.snippet.get_best_thumbnail()?,
+        name: metadata.name,
+        artist: metadata.artist,
+        platform: PretrackSourcePlatform::YouTube(video.id),
+        room_uuid: room.uuid,
+        user_uuid,
     })?;
 
-    Ok(Payload(TrackFindYouTubeResponse {
-        pretrack_uuid: pretrack.uuid.to_string()
-    }))
+    Ok(pretrack)
 }
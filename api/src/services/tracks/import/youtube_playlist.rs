@@ -0,0 +1,138 @@
+use std::collections::HashMap;
+use dal::{AddLimits, Dal, EnqueuePretrackStatus, QueueStatus, Room, RoomExt, User};
+use dal::uuid::Uuid;
+use proto::{SsePacketEvent, TrackImportFailure, TrackImportYouTubePlaylistRequest, TrackImportYouTubePlaylistResponse};
+use crate::appdata::WebData;
+use crate::error::{Error, WebResult};
+use crate::notify;
+use crate::services::tracks::find::url::parse_playlist_id;
+use crate::services::tracks::find::youtube::create_pretrack_from_video;
+use crate::services::tracks::list::{track_list_response, track_to_proto};
+use actix_multiresponse::Payload;
+use tracing::{instrument, warn};
+
+#[instrument]
+pub async fn youtube_playlist(data: WebData, payload: Payload<TrackImportYouTubePlaylistRequest>) -> WebResult<Payload<TrackImportYouTubePlaylistResponse>> {
+    let room = match Room::get(data.dal.clone(), Uuid::parse_str(&payload.room_uuid)?)? {
+        Some(x) => x,
+        None => return Err(Error::NotFound("The requested Room does not exist"))
+    };
+
+    let user = match User::get(data.dal.clone(), Uuid::parse_str(&payload.user_uuid)?)? {
+        Some(x) => x,
+        None => return Err(Error::NotFound("The requested User does not exist"))
+    };
+
+    if room.list_members()?
+        .iter()
+        .filter(|x| x.uuid.eq(&user.uuid))
+        .collect::<Vec<_>>()
+        .is_empty() {
+        return Err(Error::Forbidden("User is not in Room"));
+    }
+
+    let playlist_id = match parse_playlist_id(&payload.playlist_id) {
+        Some(x) => x,
+        None => return Err(Error::BadRequest("The provided playlist ID or URL is invalid"))
+    };
+
+    let api = data.youtube_api();
+    let limit = data.config.playlist_import_limit;
+
+    // Request one more than the limit, so we know whether the playlist was truncated
+    let mut video_ids = match api.get_playlist_video_ids(&playlist_id, limit + 1).await? {
+        Some(x) => x,
+        None => return Err(Error::NotFound("The requested YouTube playlist does not exist"))
+    };
+    let truncated = video_ids.len() > limit;
+    video_ids.truncate(limit);
+
+    let mut videos = api.get_videos(&video_ids).await?
+        .into_iter()
+        .map(|x| (x.id.clone(), x))
+        .collect::<HashMap<_, _>>();
+
+    // Moderators are not subject to approval or limits
+    let is_moderator = room.is_moderator(&user.uuid)?;
+    let pending = room.require_approval && !is_moderator;
+    let status = if pending { QueueStatus::Pending } else { QueueStatus::Enqueued };
+
+    // The cooldown applies to the import as a whole, the pending cap to every Track imported
+    let mut limits = if is_moderator { AddLimits::default() } else { room.add_limits() };
+    let queue = room.get_queue()?;
+    if let Some(exceeded) = queue.check_limits(&user.uuid, limits)? {
+        return Err(exceeded.into());
+    }
+    limits.cooldown = None;
+    let mut tracks = Vec::new();
+    let mut first_track = None;
+    let mut failures = Vec::new();
+    for video_id in video_ids {
+        let video = match videos.remove(&video_id) {
+            Some(x) => x,
+            None => {
+                failures.push(TrackImportFailure {
+                    youtube_id: video_id,
+                    reason: "The video is unavailable".to_string(),
+                });
+                continue;
+            }
+        };
+
+        if let Err(reason) = video.check_playable(room.region_code.as_deref()) {
+            failures.push(TrackImportFailure {
+                youtube_id: video_id,
+                reason: reason.to_string(),
+            });
+            continue;
+        }
+
+        let result = create_pretrack_from_video(&data, video, &room, user.uuid)
+            .and_then(|pretrack| match queue.enqueue_pretrack(pretrack, &user.uuid, status, limits)? {
+                EnqueuePretrackStatus::Ok { track, .. } => Ok((track_to_proto(&data.dal, &track)?, track)),
+                EnqueuePretrackStatus::LimitExceeded(exceeded) => Err(exceeded.into())
+            });
+
+        match result {
+            Ok((proto_track, track)) => {
+                tracks.push(proto_track);
+                first_track.get_or_insert(track);
+            },
+            Err(Error::Forbidden(reason)) => failures.push(TrackImportFailure {
+                youtube_id: video_id,
+                reason: reason.to_string(),
+            }),
+            Err(Error::LimitExceeded(exceeded)) => failures.push(TrackImportFailure {
+                youtube_id: video_id,
+                reason: exceeded.to_string(),
+            }),
+            Err(e) => {
+                warn!("Failed to import YouTube video {video_id}: {e}");
+                failures.push(TrackImportFailure {
+                    youtube_id: video_id,
+                    reason: "Failed to enqueue the video".to_string(),
+                });
+            }
+        }
+    }
+
+    // The Tracks are enqueued in order, so only the first may be up next
+    if let Some(track) = &first_track {
+        notify::track_up_next_if_next(&data, &room, track);
+    }
+
+    if !tracks.is_empty() {
+        let batch = track_list_response(&room, tracks.clone())?;
+        let sse = data.sse_track_list.lock();
+        if let Some(broadcaster) = sse.get(&room.uuid) {
+            let lock = broadcaster.lock();
+            lock.send(SsePacketEvent::TrackBatch, batch)?;
+        }
+    }
+
+    Ok(Payload(TrackImportYouTubePlaylistResponse {
+        tracks,
+        failures,
+        truncated,
+    }))
+}
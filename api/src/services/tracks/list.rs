@@ -1,9 +1,10 @@
 use actix_web::web;
-use dal::{Dal, Room, RoomExt, TrackExt};
+use dal::{Dal, Mysql, PretrackSourcePlatform, QueueStatus, Room, RoomExt, Track, TrackExt, User, SYSTEM_USER_UUID};
 use dal::uuid::Uuid;
-use proto::TrackListResponse;
+use proto::{TrackListResponse, TrackPlatform};
 use crate::appdata::WebData;
 use crate::error::{Error, WebResult};
+use crate::playback;
 use actix_multiresponse::Payload;
 
 pub async fn list(data: WebData, path: web::Path<Uuid>) -> WebResult<Payload<TrackListResponse>> {
@@ -13,17 +14,52 @@ pub async fn list(data: WebData, path: web::Path<Uuid>) -> WebResult<Payload<Tra
     };
 
     let tracks = room.list_tracks()?.into_iter()
-        .map(|x| Ok(proto::Track {
-            track_idx: x.get_queue_idx()?,
-            track_uuid: x.uuid.to_string(),
-            track_name: x.name,
-            artist_name: x.artist,
-            track_duration: x.duration,
-            thumbnail_url: x.thumbnail_url,
-        }))
+        .map(|x| track_to_proto(&data.dal, &x))
         .collect::<WebResult<Vec<_>>>()?;
 
-    Ok(Payload(TrackListResponse {
-        tracks
-    }))
-}
\ No newline at end of file
+    Ok(Payload(track_list_response(&room, tracks)?))
+}
+
+/// Build a track listing, including when each Track is expected to start
+pub fn track_list_response(room: &Room<Mysql>, mut tracks: Vec<proto::Track>) -> WebResult<TrackListResponse> {
+    let schedule = playback::schedule(room)?;
+    for track in &mut tracks {
+        track.estimated_start = schedule.start_times.get(&Uuid::parse_str(&track.track_uuid)?).copied();
+    }
+
+    Ok(TrackListResponse {
+        tracks,
+        remaining_duration: schedule.remaining,
+    })
+}
+
+/// Convert a Track to its protobuf representation, looking up its entry in the queue and who added it
+pub fn track_to_proto(dal: &Mysql, track: &Track<Mysql>) -> WebResult<proto::Track> {
+    let entry = track.get_queue_entry()?;
+    let added_by_name = match entry {
+        Some(entry) if entry.added_by.ne(&SYSTEM_USER_UUID) => User::get(dal.clone(), entry.added_by)?.map(|x| x.name),
+        _ => None
+    };
+
+    let platform = match track.platform {
+        PretrackSourcePlatform::YouTube(_) => TrackPlatform::YouTube,
+        PretrackSourcePlatform::Spotify(_) => TrackPlatform::Spotify,
+    };
+
+    Ok(proto::Track {
+        track_uuid: track.uuid.to_string(),
+        track_name: track.name.clone(),
+        artist_name: track.artist.clone(),
+        track_duration: track.duration,
+        thumbnail_url: track.thumbnail_url.clone(),
+        track_idx: entry.map(|x| x.idx).unwrap_or(-1),
+        auto_added: entry.map(|x| x.added_by == SYSTEM_USER_UUID).unwrap_or(false),
+        pending: entry.map(|x| x.status == QueueStatus::Pending).unwrap_or(false),
+        platform: platform.into(),
+        platform_track_id: track.platform.get_track_id(),
+        added_by_uuid: entry.map(|x| x.added_by.to_string()),
+        added_by_name,
+        added_at: entry.map(|x| x.added_at),
+        estimated_start: None,
+    })
+}
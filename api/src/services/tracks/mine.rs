@@ -0,0 +1,37 @@
+use actix_web::web;
+use serde::Deserialize;
+use dal::{Dal, Room, RoomExt};
+use dal::uuid::Uuid;
+use proto::TrackListResponse;
+use crate::appdata::WebData;
+use crate::error::{Error, WebResult};
+use crate::services::tracks::list::{track_list_response, track_to_proto};
+use actix_multiresponse::Payload;
+use tracing::instrument;
+
+#[derive(Deserialize, Debug)]
+pub struct Query {
+    room_uuid: Uuid,
+    user_uuid: Uuid,
+}
+
+/// List the Tracks in the queue added by a User, including Tracks awaiting approval
+#[instrument]
+pub async fn mine(data: WebData, query: web::Query<Query>) -> WebResult<Payload<TrackListResponse>> {
+    let room = match Room::get(data.dal.clone(), query.room_uuid)? {
+        Some(x) => x,
+        None => return Err(Error::NotFound("The requested Room does not exist"))
+    };
+
+    if room.list_members()?.iter().all(|x| x.uuid.ne(&query.user_uuid)) {
+        return Err(Error::Forbidden("User is not in Room"));
+    }
+
+    let tracks = room.get_queue()?
+        .get_added_by(&query.user_uuid)?
+        .into_iter()
+        .map(|x| track_to_proto(&data.dal, &x))
+        .collect::<WebResult<Vec<_>>>()?;
+
+    Ok(Payload(track_list_response(&room, tracks)?))
+}
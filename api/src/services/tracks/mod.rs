@@ -2,3 +2,8 @@ pub mod sse_list;
 pub mod list;
 pub mod add;
 pub mod find;
+pub mod import;
+pub mod skip_vote;
+pub mod moderate;
+pub mod remove;
+pub mod mine;
\ No newline at end of file
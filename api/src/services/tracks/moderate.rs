@@ -0,0 +1,100 @@
+use dal::{Dal, Mysql, QueueStatus, Room, RoomExt, Track, TrackExt};
+use dal::uuid::Uuid;
+use proto::{SsePacketEvent, TrackApproveRequest, TrackModeratedResponse, TrackRejectRequest, UserNotification, UserNotificationKind};
+use crate::appdata::WebData;
+use crate::error::{Error, WebResult};
+use crate::notify;
+use crate::services::tracks::list::track_to_proto;
+use actix_multiresponse::Payload;
+use tracing::instrument;
+
+/// The maximum length of a rejection reason
+const MAX_REASON_LENGTH: usize = 256;
+
+#[instrument]
+pub async fn approve(data: WebData, payload: Payload<TrackApproveRequest>) -> WebResult<Payload<TrackModeratedResponse>> {
+    let (room, track, requester) = get_pending(&data, &payload.room_uuid, &payload.user_uuid, &payload.track_uuid)?;
+    if room.get_queue()?.approve(&track)?.is_none() {
+        return Err(Error::Conflict("The requested Track is not awaiting approval".to_string()));
+    }
+    notify::track_up_next_if_next(&data, &room, &track);
+
+    let response = TrackModeratedResponse {
+        track: Some(track_to_proto(&data.dal, &track)?),
+        requester_uuid: requester.to_string(),
+        approved: true,
+        reason: None,
+    };
+
+    broadcast(&data, &room, &response)?;
+    Ok(Payload(response))
+}
+
+#[instrument]
+pub async fn reject(data: WebData, payload: Payload<TrackRejectRequest>) -> WebResult<Payload<TrackModeratedResponse>> {
+    if payload.reason.as_ref().map(|x| x.len() > MAX_REASON_LENGTH).unwrap_or(false) {
+        return Err(Error::BadRequest("Reason may not be longer than 256 characters"));
+    }
+
+    let (room, track, requester) = get_pending(&data, &payload.room_uuid, &payload.user_uuid, &payload.track_uuid)?;
+    // Describe the Track as it was in the queue, before removing it
+    let proto_track = track_to_proto(&data.dal, &track)?;
+    room.get_queue()?.dequeue(&track)?;
+
+    let response = TrackModeratedResponse {
+        track: Some(proto_track),
+        requester_uuid: requester.to_string(),
+        approved: false,
+        reason: payload.reason.clone(),
+    };
+
+    // A rejected Track was never played, so it should not end up in the Room's history
+    track.delete()?;
+
+    notify::send(&data, requester, UserNotification {
+        kind: UserNotificationKind::Rejected.into(),
+        room_uuid: room.uuid.to_string(),
+        track: response.track.clone(),
+        reason: payload.reason.clone(),
+    });
+
+    broadcast(&data, &room, &response)?;
+    Ok(Payload(response))
+}
+
+/// Get a pending Track, checking that the User may moderate the Room.
+/// Returns the Room, the Track and the User who added the Track
+fn get_pending(data: &WebData, room_uuid: &str, user_uuid: &str, track_uuid: &str) -> WebResult<(Room<Mysql>, Track<Mysql>, Uuid)> {
+    let room = match Room::get(data.dal.clone(), Uuid::parse_str(room_uuid)?)? {
+        Some(x) => x,
+        None => return Err(Error::NotFound("The requested Room does not exist"))
+    };
+
+    if !room.is_moderator(&Uuid::parse_str(user_uuid)?)? {
+        return Err(Error::Forbidden("Only the owner and co-hosts of the Room may moderate Tracks"));
+    }
+
+    let track = match Track::get(data.dal.clone(), Uuid::parse_str(track_uuid)?)? {
+        Some(x) if x.room_uuid.eq(&room.uuid) => x,
+        _ => return Err(Error::NotFound("The requested Track does not exist"))
+    };
+
+    if track.get_queue_status()? != Some(QueueStatus::Pending) {
+        return Err(Error::Conflict("The requested Track is not awaiting approval".to_string()));
+    }
+
+    let requester = track.get_added_by()?
+        .ok_or(Error::Conflict("The requested Track is not awaiting approval".to_string()))?;
+
+    Ok((room, track, requester))
+}
+
+fn broadcast(data: &WebData, room: &Room<Mysql>, response: &TrackModeratedResponse) -> WebResult<()> {
+    let sse = data.sse_track_list.lock();
+    if let Some(broadcaster) = sse.get(&room.uuid) {
+        let lock = broadcaster.lock();
+        lock.send(SsePacketEvent::TrackModerated, response.clone())?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use dal::{Dal, Room, RoomExt, Track, TrackExt};
+use dal::uuid::Uuid;
+use proto::{SsePacketEvent, TrackRemoveRequest, TrackRemoveResponse, UserNotification, UserNotificationKind};
+use crate::appdata::WebData;
+use crate::error::{Error, WebResult};
+use crate::{notify, playback};
+use crate::services::tracks::list::track_to_proto;
+use actix_multiresponse::Payload;
+use tracing::instrument;
+
+#[instrument]
+pub async fn remove(data: WebData, payload: Payload<TrackRemoveRequest>) -> WebResult<Payload<TrackRemoveResponse>> {
+    let room = match Room::get(data.dal.clone(), Uuid::parse_str(&payload.room_uuid)?)? {
+        Some(x) => x,
+        None => return Err(Error::NotFound("The requested Room does not exist"))
+    };
+
+    let user_uuid = Uuid::parse_str(&payload.user_uuid)?;
+    let track = match Track::get(data.dal.clone(), Uuid::parse_str(&payload.track_uuid)?)? {
+        Some(x) if x.room_uuid.eq(&room.uuid) => x,
+        _ => return Err(Error::NotFound("The requested Track does not exist"))
+    };
+
+    let added_by = match track.get_added_by()? {
+        Some(x) => x,
+        None => return Err(Error::Conflict("The requested Track is not in the queue".to_string()))
+    };
+
+    if added_by.ne(&user_uuid) && !room.is_moderator(&user_uuid)? {
+        return Err(Error::Forbidden("Only the User who added the Track and moderators of the Room may remove it"));
+    }
+
+    let response = TrackRemoveResponse {
+        track_uuid: track.uuid.to_string()
+    };
+
+    // Describe the Track as it was in the queue, before removing it
+    let notification = if added_by.ne(&user_uuid) {
+        Some(UserNotification {
+            kind: UserNotificationKind::Removed.into(),
+            room_uuid: room.uuid.to_string(),
+            track: Some(track_to_proto(&data.dal, &track)?),
+            reason: None,
+        })
+    } else {
+        None
+    };
+
+    let mut playback = room.get_playback()?;
+    if playback.track_uuid == Some(track.uuid) {
+        // The Track is playing, move on to the next one
+        playback::advance(&data, &room, &mut playback).await?;
+        playback::save(&data, &playback)?;
+    } else {
+        // The Track was never played, so it should not end up in the Room's history
+        room.get_queue()?.dequeue(&track)?;
+        track.delete()?;
+    }
+
+    let sse = data.sse_track_list.lock();
+    if let Some(broadcaster) = sse.get(&room.uuid) {
+        let lock = broadcaster.lock();
+        lock.send(SsePacketEvent::TrackRemoved, response.clone())?;
+    }
+
+    // Let the User know a moderator removed their Track
+    if let Some(notification) = notification {
+        notify::send(&data, added_by, notification);
+    }
+
+    Ok(Payload(response))
+}
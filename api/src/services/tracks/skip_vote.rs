@@ -0,0 +1,77 @@
+use dal::{Dal, Room, RoomExt, User};
+use dal::uuid::Uuid;
+use proto::{SsePacketEvent, TrackSkipVoteRequest, TrackSkipVoteResponse};
+use crate::appdata::WebData;
+use crate::error::{Error, WebResult};
+use crate::{playback, presence};
+use actix_multiresponse::Payload;
+use tracing::instrument;
+
+#[instrument]
+pub async fn skip_vote(data: WebData, payload: Payload<TrackSkipVoteRequest>) -> WebResult<Payload<TrackSkipVoteResponse>> {
+    let room = match Room::get(data.dal.clone(), Uuid::parse_str(&payload.room_uuid)?)? {
+        Some(x) => x,
+        None => return Err(Error::NotFound("The requested Room does not exist"))
+    };
+
+    let user = match User::get(data.dal.clone(), Uuid::parse_str(&payload.user_uuid)?)? {
+        Some(x) => x,
+        None => return Err(Error::NotFound("The requested User does not exist"))
+    };
+
+    let members = room.list_members()?;
+    if members.iter().all(|x| x.uuid.ne(&user.uuid)) {
+        return Err(Error::Forbidden("User is not in Room"));
+    }
+
+    let track_uuid = Uuid::parse_str(&payload.track_uuid)?;
+
+    // Votes crossing the threshold at the same time must not skip more than one Track.
+    // Votes of a Room are serialized, so later votes see the Track has changed
+    let lock = data.skip_vote_locks.lock()
+        .entry(room.uuid)
+        .or_default()
+        .clone();
+    let _guard = lock.lock().await;
+
+    let mut playback = room.get_playback()?;
+    if playback.track_uuid != Some(track_uuid) {
+        return Err(Error::Conflict("The requested Track is not playing".to_string()));
+    }
+
+    let votes = room.get_skip_votes(track_uuid)?;
+    let already_voted = !votes.add(&user.uuid)?;
+
+    // Members who are not connected do not count towards the threshold. The voter is present either way
+    let present = presence::present_members(&data, &room)?
+        .iter()
+        .filter(|x| x.uuid.ne(&user.uuid))
+        .count() + 1;
+
+    let vote_count = votes.count()?;
+    let required = room.skip_threshold.required(present);
+    let skipped = room.owner.eq(&user.uuid) || vote_count >= required;
+
+    let response = TrackSkipVoteResponse {
+        track_uuid: track_uuid.to_string(),
+        votes: vote_count,
+        required,
+        skipped,
+        already_voted,
+    };
+
+    {
+        let sse = data.sse_track_list.lock();
+        if let Some(broadcaster) = sse.get(&room.uuid) {
+            let lock = broadcaster.lock();
+            lock.send(SsePacketEvent::SkipVote, response.clone())?;
+        }
+    }
+
+    if skipped {
+        playback::advance(&data, &room, &mut playback).await?;
+        playback::save(&data, &playback)?;
+    }
+
+    Ok(Payload(response))
+}
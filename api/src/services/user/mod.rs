@@ -1 +1,3 @@
-pub mod get;
\ No newline at end of file
+pub mod get;
+pub mod rooms;
+pub mod sse;
\ No newline at end of file
@@ -0,0 +1,52 @@
+use actix_web::web;
+use serde::Deserialize;
+use dal::{Dal, MemberRole, Room, User, UserExt};
+use dal::uuid::Uuid;
+use proto::{RoomRole, UserRoom, UserRoomsResponse};
+use crate::appdata::WebData;
+use crate::error::{Error, WebResult};
+use crate::presence;
+use actix_multiresponse::Payload;
+use tracing::instrument;
+
+#[derive(Deserialize, Debug)]
+pub struct Query {
+    user_uuid: Uuid,
+}
+
+/// List the Rooms a User is a member of
+#[instrument]
+pub async fn rooms(data: WebData, query: web::Query<Query>) -> WebResult<Payload<UserRoomsResponse>> {
+    let user = match User::get(data.dal.clone(), query.user_uuid)? {
+        Some(x) => x,
+        None => return Err(Error::NotFound("The requested user does not exist"))
+    };
+
+    let mut rooms = Vec::new();
+    for user_room in user.list_rooms()? {
+        let present_count = match Room::get(data.dal.clone(), user_room.room_uuid)? {
+            Some(room) => presence::present_members(&data, &room)?.len() as u32,
+            // The Room was deleted since listing
+            None => continue,
+        };
+
+        let role = match user_room.role {
+            MemberRole::Owner => RoomRole::Owner,
+            MemberRole::CoHost => RoomRole::CoHost,
+            MemberRole::Member => RoomRole::Member,
+        };
+
+        rooms.push(UserRoom {
+            room_uuid: user_room.room_uuid.to_string(),
+            name: user_room.name,
+            role: role.into(),
+            joined_at: user_room.joined_at,
+            member_count: user_room.member_count,
+            present_count,
+        });
+    }
+
+    Ok(Payload(UserRoomsResponse {
+        rooms
+    }))
+}
\ No newline at end of file
@@ -0,0 +1,34 @@
+use crate::appdata::WebData;
+use crate::error::{Error, WebResult};
+use crate::services::sse::broadcaster::{Broadcaster, SseResponse};
+use actix_web::web;
+use tracing::trace;
+use dal::{Dal, User};
+use dal::uuid::Uuid;
+use crate::services::sse::x_accept::XAccept;
+
+/// Event stream carrying notifications meant for a single User
+pub async fn sse(data: WebData, path: web::Path<Uuid>, xaccept: XAccept) -> WebResult<SseResponse> {
+    let uuid = path.into_inner();
+    if User::get(data.dal.clone(), uuid)?.is_none() {
+        return Err(Error::NotFound("The requested user does not exist"));
+    }
+
+    let mut map = data.sse_user.lock();
+    let am_broadcaster = match map.get(&uuid) {
+        Some(x) => {
+            trace!("Map already has Broadcaster for User {uuid}");
+            x.clone()
+        },
+        None => {
+            trace!("No Broadcaster exists for User {uuid}, creating");
+            let am_broadcaster = Broadcaster::new(uuid);
+            map.insert(uuid, am_broadcaster.clone());
+            am_broadcaster
+        }
+    };
+
+    let mut broadcaster = am_broadcaster.lock();
+    let client = broadcaster.new_client((*xaccept).clone())?;
+    Ok(client.into())
+}
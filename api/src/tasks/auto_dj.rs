@@ -0,0 +1,32 @@
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::{interval_at, Instant};
+use tracing::warn;
+use dal::{Room, RoomExt};
+use crate::appdata::AppData;
+use crate::auto_dj;
+
+/// Periodically fill the queues of Rooms with autoplay enabled which have run dry
+pub fn spawn(appdata: Arc<AppData>) {
+    actix_rt::spawn(async move {
+        let interval = Duration::from_secs(appdata.config.auto_dj_interval);
+        let mut task = interval_at(Instant::now() + interval, interval);
+        loop {
+            task.tick().await;
+
+            let rooms = match Room::list_autoplay(appdata.dal.clone()) {
+                Ok(x) => x,
+                Err(e) => {
+                    warn!("Failed to list Rooms with autoplay enabled: {e}");
+                    continue;
+                }
+            };
+
+            for room in rooms {
+                if let Err(e) = auto_dj::fill(&appdata, &room).await {
+                    warn!("Failed to autoplay in Room {}: {e}", room.uuid);
+                }
+            }
+        }
+    });
+}
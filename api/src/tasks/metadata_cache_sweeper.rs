@@ -0,0 +1,25 @@
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::{interval_at, Instant};
+use tracing::{debug, warn};
+use dal::MetadataCache;
+use crate::appdata::AppData;
+
+/// Periodically delete expired metadata from the database
+pub fn spawn(appdata: Arc<AppData>) {
+    actix_rt::spawn(async move {
+        let interval = Duration::from_secs(appdata.config.youtube_cache_sweep_interval);
+        let mut task = interval_at(Instant::now() + interval, interval);
+        let cache = MetadataCache::new(appdata.dal.clone());
+        let ttl = appdata.config.youtube_video_cache_ttl.max(appdata.config.youtube_search_cache_ttl);
+        loop {
+            task.tick().await;
+
+            let cutoff = time::OffsetDateTime::now_utc().unix_timestamp() - ttl as i64;
+            match cache.delete_cached_before(cutoff) {
+                Ok(deleted) => debug!("Deleted {deleted} expired metadata cache entries"),
+                Err(e) => warn!("Failed to delete expired metadata cache entries: {e}"),
+            }
+        }
+    });
+}
@@ -0,0 +1,4 @@
+pub mod pretrack_sweeper;
+pub mod metadata_cache_sweeper;
+pub mod auto_dj;
+pub mod player_failover;
@@ -0,0 +1,41 @@
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::{interval_at, Instant};
+use tracing::{debug, warn};
+use dal::{PlayerDevice, PlayerDeviceExt};
+use crate::appdata::AppData;
+use crate::player;
+
+/// Periodically replace active player devices which missed their heartbeats, and delete devices which are gone
+pub fn spawn(appdata: Arc<AppData>) {
+    actix_rt::spawn(async move {
+        let interval = Duration::from_secs(appdata.config.player_failover_interval);
+        let mut task = interval_at(Instant::now() + interval, interval);
+        loop {
+            task.tick().await;
+
+            let now = time::OffsetDateTime::now_utc().unix_timestamp();
+            let cutoff = now - appdata.config.player_heartbeat_timeout as i64;
+            let stale = match PlayerDevice::list_active_heartbeat_before(appdata.dal.clone(), cutoff) {
+                Ok(x) => x,
+                Err(e) => {
+                    warn!("Failed to list player devices which missed their heartbeats: {e}");
+                    continue;
+                }
+            };
+
+            for device in stale {
+                let uuid = device.uuid;
+                debug!("Player device {uuid} in Room {} missed its heartbeats", device.room_uuid);
+                if let Err(e) = player::failover(&appdata, device) {
+                    warn!("Failed to fail over player device {uuid}: {e}");
+                }
+            }
+
+            match PlayerDevice::delete_heartbeat_before(appdata.dal.clone(), now - appdata.config.player_device_ttl as i64) {
+                Ok(deleted) => debug!("Deleted {deleted} gone player device(s)"),
+                Err(e) => warn!("Failed to delete gone player devices: {e}"),
+            }
+        }
+    });
+}
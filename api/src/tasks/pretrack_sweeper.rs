@@ -0,0 +1,23 @@
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::{interval_at, Instant};
+use tracing::{debug, warn};
+use dal::{Pretrack, PretrackExt};
+use crate::appdata::AppData;
+
+/// Periodically delete Pretracks which were never turned into a Track
+pub fn spawn(appdata: Arc<AppData>) {
+    actix_rt::spawn(async move {
+        let interval = Duration::from_secs(appdata.config.pretrack_sweep_interval);
+        let mut task = interval_at(Instant::now() + interval, interval);
+        loop {
+            task.tick().await;
+
+            let cutoff = time::OffsetDateTime::now_utc().unix_timestamp() - appdata.config.pretrack_ttl as i64;
+            match Pretrack::delete_created_before(appdata.dal.clone(), cutoff) {
+                Ok(deleted) => debug!("Deleted {deleted} expired Pretrack(s)"),
+                Err(e) => warn!("Failed to delete expired Pretracks: {e}"),
+            }
+        }
+    });
+}
@@ -0,0 +1,9 @@
+CREATE TABLE playback (
+    id INT NOT NULL PRIMARY KEY AUTO_INCREMENT,
+    room_uuid BLOB NOT NULL,
+    track_uuid BLOB NULL,
+    status VARCHAR(16) NOT NULL,
+    position BIGINT NOT NULL,
+    reference_time BIGINT NOT NULL,
+    UNIQUE KEY (room_uuid(16))
+);
\ No newline at end of file
@@ -0,0 +1,10 @@
+CREATE TABLE player_devices (
+    id INT NOT NULL PRIMARY KEY AUTO_INCREMENT,
+    uuid BLOB NOT NULL,
+    room_uuid BLOB NOT NULL,
+    user_uuid BLOB NOT NULL,
+    name VARCHAR(64) NOT NULL,
+    registered_at BIGINT NOT NULL,
+    last_heartbeat BIGINT NOT NULL,
+    active BOOLEAN NOT NULL DEFAULT FALSE
+);
\ No newline at end of file
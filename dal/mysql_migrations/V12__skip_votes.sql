@@ -0,0 +1,10 @@
+ALTER TABLE rooms ADD COLUMN skip_threshold_kind VARCHAR(16) NOT NULL DEFAULT 'Percentage', ADD COLUMN skip_threshold INT NOT NULL DEFAULT 50;
+
+CREATE TABLE skip_votes (
+    id INT NOT NULL PRIMARY KEY AUTO_INCREMENT,
+    room_uuid BLOB NOT NULL,
+    track_uuid BLOB NOT NULL,
+    user_uuid BLOB NOT NULL,
+    voted_at BIGINT NOT NULL,
+    UNIQUE KEY (track_uuid(16), user_uuid(16))
+);
\ No newline at end of file
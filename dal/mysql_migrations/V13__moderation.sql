@@ -0,0 +1,3 @@
+ALTER TABLE rooms ADD COLUMN require_approval BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE room_members ADD COLUMN co_host BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE queue ADD COLUMN status VARCHAR(16) NOT NULL DEFAULT 'Enqueued';
\ No newline at end of file
@@ -0,0 +1,8 @@
+CREATE TABLE blocklist (
+    id INT NOT NULL PRIMARY KEY AUTO_INCREMENT,
+    uuid BLOB NOT NULL,
+    room_uuid BLOB NULL,
+    kind VARCHAR(16) NOT NULL,
+    value VARCHAR(256) NOT NULL,
+    created_at BIGINT NOT NULL
+);
\ No newline at end of file
@@ -0,0 +1,6 @@
+ALTER TABLE rooms ADD COLUMN max_pending_per_user INT NULL, ADD COLUMN add_cooldown BIGINT NULL;
+ALTER TABLE queue ADD COLUMN added_at BIGINT NOT NULL DEFAULT 0;
+-- Kept apart from the queue, so removing a Track does not reset the cooldown
+ALTER TABLE room_members ADD COLUMN last_added_at BIGINT NULL;
+-- Adds lock the Room row, without an index every row scanned would be locked
+CREATE INDEX rooms_uuid ON rooms (uuid(16));
\ No newline at end of file
@@ -0,0 +1,9 @@
+CREATE TABLE push_subscriptions (
+    id INT NOT NULL PRIMARY KEY AUTO_INCREMENT,
+    uuid BLOB NOT NULL,
+    user_uuid BLOB NOT NULL,
+    endpoint VARCHAR(1024) NOT NULL,
+    p256dh VARCHAR(256) NOT NULL,
+    auth VARCHAR(256) NOT NULL,
+    created_at BIGINT NOT NULL
+);
\ No newline at end of file
@@ -0,0 +1,5 @@
+ALTER TABLE users ADD COLUMN secret VARCHAR(64) NULL;
+
+-- Remove duplicate memberships created by rejoining, keeping the oldest
+DELETE a FROM room_members a JOIN room_members b ON a.room_uuid = b.room_uuid AND a.user_uuid = b.user_uuid AND a.id > b.id;
+ALTER TABLE room_members ADD UNIQUE KEY (room_uuid(16), user_uuid(16));
\ No newline at end of file
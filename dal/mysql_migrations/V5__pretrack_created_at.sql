@@ -0,0 +1 @@
+ALTER TABLE pretracks ADD COLUMN created_at BIGINT NOT NULL DEFAULT 0;
\ No newline at end of file
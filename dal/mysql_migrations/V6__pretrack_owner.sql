@@ -0,0 +1,2 @@
+DELETE FROM pretracks;
+ALTER TABLE pretracks ADD COLUMN room_uuid BLOB NOT NULL, ADD COLUMN user_uuid BLOB NOT NULL;
\ No newline at end of file
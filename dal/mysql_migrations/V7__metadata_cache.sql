@@ -0,0 +1,5 @@
+CREATE TABLE metadata_cache (
+    cache_key VARCHAR(512) NOT NULL PRIMARY KEY,
+    data MEDIUMTEXT NOT NULL,
+    cached_at BIGINT NOT NULL
+);
\ No newline at end of file
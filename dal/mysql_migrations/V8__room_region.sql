@@ -0,0 +1 @@
+ALTER TABLE rooms ADD COLUMN region_code VARCHAR(2) NULL;
\ No newline at end of file
@@ -0,0 +1 @@
+ALTER TABLE rooms ADD COLUMN autoplay BOOLEAN NOT NULL DEFAULT FALSE;
\ No newline at end of file
@@ -0,0 +1,166 @@
+use mysql::prelude::Queryable;
+use mysql_common::params;
+use mysql_common::row::Row;
+use uuid::Uuid;
+use crate::{Dal, DalResult, Datastore, Error, Mysql};
+
+/// What a blocklist entry matches on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlocklistKind {
+    /// The ID of a track on its platform, e.g. a YouTube video ID
+    PlatformId,
+    Artist,
+    /// A keyword in the name of a track
+    Keyword,
+}
+
+impl BlocklistKind {
+    fn new(kind: &str) -> Option<Self> {
+        match kind {
+            "PlatformId" => Some(Self::PlatformId),
+            "Artist" => Some(Self::Artist),
+            "Keyword" => Some(Self::Keyword),
+            _ => None
+        }
+    }
+}
+
+impl ToString for BlocklistKind {
+    fn to_string(&self) -> String {
+        match self {
+            Self::PlatformId => "PlatformId".into(),
+            Self::Artist => "Artist".into(),
+            Self::Keyword => "Keyword".into(),
+        }
+    }
+}
+
+pub struct BlocklistEntry<T: Datastore> {
+    dal: T,
+    pub uuid: Uuid,
+    /// The Room the entry applies to. Global entries apply to all Rooms
+    pub room_uuid: Option<Uuid>,
+    pub kind: BlocklistKind,
+    pub value: String,
+    pub created_at: i64,
+}
+
+pub struct BlocklistEntryBuildable {
+    pub room_uuid: Option<Uuid>,
+    pub kind: BlocklistKind,
+    pub value: String,
+}
+
+/// The maximum length of the value of a blocklist entry
+pub const MAX_BLOCKLIST_VALUE_LENGTH: usize = 256;
+
+pub trait BlocklistEntryExt<T: Datastore, U>: Dal<T, U> {
+    /// List the entries of a Room, or the global entries if no Room is provided
+    fn list(dal: T, room_uuid: Option<Uuid>) -> DalResult<Vec<Self>>;
+    /// List the entries which apply to a Room: its own and the global entries
+    fn list_applicable(dal: T, room_uuid: Uuid) -> DalResult<Vec<Self>>;
+}
+
+impl Dal<Mysql, BlocklistEntryBuildable> for BlocklistEntry<Mysql> {
+    fn get(dal: Mysql, uuid: Uuid) -> DalResult<Option<Self>> {
+        let mut conn = dal.get_conn()?;
+        let row: Row = match conn.exec_first("SELECT room_uuid,kind,value,created_at FROM blocklist WHERE uuid = :uuid", params! {
+            "uuid" => &uuid
+        })? {
+            Some(x) => x,
+            None => return Ok(None)
+        };
+
+        let kind: String = row.get("kind").unwrap();
+        let kind = BlocklistKind::new(&kind)
+            .ok_or(Error::Other(format!("Unknown blocklist kind '{kind}'")))?;
+
+        Ok(Some(Self {
+            dal,
+            uuid,
+            room_uuid: row.get("room_uuid").unwrap(),
+            kind,
+            value: row.get("value").unwrap(),
+            created_at: row.get("created_at").unwrap(),
+        }))
+    }
+
+    fn delete(self) -> DalResult<()> {
+        let mut conn = self.dal.get_conn()?;
+        conn.exec_drop("DELETE FROM blocklist WHERE uuid = :uuid", params! {
+            "uuid" => &self.uuid
+        })?;
+
+        Ok(())
+    }
+
+    fn update(&mut self) -> DalResult<()> {
+        let mut conn = self.dal.get_conn()?;
+        conn.exec_drop("UPDATE blocklist SET kind = :kind, value = :value WHERE uuid = :uuid", params! {
+            "kind" => self.kind.to_string(),
+            "value" => &self.value,
+            "uuid" => &self.uuid,
+        })?;
+
+        Ok(())
+    }
+
+    fn create(dal: Mysql, buildable: BlocklistEntryBuildable) -> DalResult<Self> {
+        let mut conn = dal.get_conn()?;
+        let uuid = Uuid::new_v4();
+        let created_at = time::OffsetDateTime::now_utc().unix_timestamp();
+        conn.exec_drop("INSERT INTO blocklist (uuid, room_uuid, kind, value, created_at) VALUES (:uuid, :room_uuid, :kind, :value, :created_at)", params! {
+            "uuid" => &uuid,
+            "room_uuid" => &buildable.room_uuid,
+            "kind" => buildable.kind.to_string(),
+            "value" => &buildable.value,
+            "created_at" => created_at,
+        })?;
+
+        Ok(Self {
+            dal,
+            uuid,
+            room_uuid: buildable.room_uuid,
+            kind: buildable.kind,
+            value: buildable.value,
+            created_at,
+        })
+    }
+}
+
+impl BlocklistEntryExt<Mysql, BlocklistEntryBuildable> for BlocklistEntry<Mysql> {
+    fn list(dal: Mysql, room_uuid: Option<Uuid>) -> DalResult<Vec<Self>> {
+        let mut conn = dal.get_conn()?;
+        let rows: Vec<Row> = match room_uuid {
+            Some(room_uuid) => conn.exec("SELECT uuid FROM blocklist WHERE room_uuid = :room_uuid ORDER BY created_at ASC", params! {
+                "room_uuid" => &room_uuid
+            })?,
+            None => conn.exec("SELECT uuid FROM blocklist WHERE room_uuid IS NULL ORDER BY created_at ASC", ())?,
+        };
+
+        Self::get_all(dal, rows)
+    }
+
+    fn list_applicable(dal: Mysql, room_uuid: Uuid) -> DalResult<Vec<Self>> {
+        let mut conn = dal.get_conn()?;
+        let rows: Vec<Row> = conn.exec("SELECT uuid FROM blocklist WHERE room_uuid = :room_uuid OR room_uuid IS NULL", params! {
+            "room_uuid" => &room_uuid
+        })?;
+
+        Self::get_all(dal, rows)
+    }
+}
+
+impl BlocklistEntry<Mysql> {
+    fn get_all(dal: Mysql, rows: Vec<Row>) -> DalResult<Vec<Self>> {
+        let entries = rows.into_iter()
+            .map(|x| x.get::<Uuid, &str>("uuid").unwrap())
+            .map(|x| Self::get(dal.clone(), x))
+            .collect::<DalResult<Vec<_>>>()?
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+
+        Ok(entries)
+    }
+}
@@ -4,6 +4,13 @@ mod mysql_dal;
 mod pretrack;
 mod track;
 mod queue;
+mod metadata_cache;
+mod playback;
+mod player_device;
+mod skip_vote;
+mod blocklist;
+mod push_subscription;
+mod util;
 
 pub use user::*;
 pub use room::*;
@@ -11,6 +18,13 @@ pub use mysql_dal::*;
 pub use pretrack::*;
 pub use track::*;
 pub use queue::*;
+pub use metadata_cache::*;
+pub use playback::*;
+pub use player_device::*;
+pub use skip_vote::*;
+pub use blocklist::*;
+pub use push_subscription::*;
+pub use util::*;
 
 pub mod uuid {
     pub use ::uuid::Uuid;
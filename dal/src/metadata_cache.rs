@@ -0,0 +1,69 @@
+use mysql::prelude::Queryable;
+use mysql_common::params;
+use mysql_common::row::Row;
+use crate::{DalResult, Datastore, Mysql};
+
+/// Persistent storage for metadata retrieved from external platforms
+pub struct MetadataCache<T: Datastore> {
+    dal: T,
+}
+
+pub struct CachedMetadata {
+    pub data: String,
+    /// UNIX timestamp at which the data was cached
+    pub cached_at: i64,
+}
+
+/// The maximum length of a cache key
+pub const MAX_CACHE_KEY_LENGTH: usize = 512;
+
+impl MetadataCache<Mysql> {
+    pub fn new(dal: Mysql) -> Self {
+        Self {
+            dal
+        }
+    }
+
+    pub fn get<S: AsRef<str>>(&self, key: S) -> DalResult<Option<CachedMetadata>> {
+        let mut conn = self.dal.get_conn()?;
+        let row: Row = match conn.exec_first("SELECT data,cached_at FROM metadata_cache WHERE cache_key = :cache_key", params! {
+            "cache_key" => key.as_ref()
+        })? {
+            Some(x) => x,
+            None => return Ok(None)
+        };
+
+        Ok(Some(CachedMetadata {
+            data: row.get("data").unwrap(),
+            cached_at: row.get("cached_at").unwrap(),
+        }))
+    }
+
+    /// Insert or replace the data cached under `key`.
+    /// Keys longer than [MAX_CACHE_KEY_LENGTH] are not stored
+    pub fn set(&self, key: &str, data: &str, cached_at: i64) -> DalResult<()> {
+        if key.chars().count() > MAX_CACHE_KEY_LENGTH {
+            return Ok(());
+        }
+
+        let mut conn = self.dal.get_conn()?;
+        conn.exec_drop("INSERT INTO metadata_cache (cache_key, data, cached_at) VALUES (:cache_key, :data, :cached_at) ON DUPLICATE KEY UPDATE data = VALUES(data), cached_at = VALUES(cached_at)", params! {
+            "cache_key" => key,
+            "data" => data,
+            "cached_at" => cached_at,
+        })?;
+
+        Ok(())
+    }
+
+    /// Delete all entries cached before the provided UNIX timestamp.
+    /// Returns the amount of entries deleted
+    pub fn delete_cached_before(&self, timestamp: i64) -> DalResult<u64> {
+        let mut conn = self.dal.get_conn()?;
+        conn.exec_drop("DELETE FROM metadata_cache WHERE cached_at < :timestamp", params! {
+            "timestamp" => timestamp
+        })?;
+
+        Ok(conn.affected_rows())
+    }
+}
@@ -0,0 +1,154 @@
+use mysql::prelude::Queryable;
+use mysql_common::params;
+use mysql_common::params::Params;
+use mysql_common::row::Row;
+use uuid::Uuid;
+use crate::{DalResult, Datastore, Error, Mysql};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaybackStatus {
+    Stopped,
+    Playing,
+    Paused,
+}
+
+impl PlaybackStatus {
+    fn new(status: &str) -> Option<Self> {
+        match status {
+            "Stopped" => Some(Self::Stopped),
+            "Playing" => Some(Self::Playing),
+            "Paused" => Some(Self::Paused),
+            _ => None
+        }
+    }
+}
+
+impl ToString for PlaybackStatus {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Stopped => "Stopped".into(),
+            Self::Playing => "Playing".into(),
+            Self::Paused => "Paused".into(),
+        }
+    }
+}
+
+/// The authoritative playback state of a Room.
+/// At server time `reference_time`, playback is at `position` into the current Track.
+/// While playing, the position advances in step with the server clock
+pub struct Playback<T: Datastore> {
+    pub(crate) dal: T,
+    pub room_uuid: Uuid,
+    pub track_uuid: Option<Uuid>,
+    pub status: PlaybackStatus,
+    /// Position into the Track in milliseconds
+    pub position: i64,
+    /// UNIX timestamp in milliseconds at which `position` applies
+    pub reference_time: i64,
+}
+
+impl<T: Datastore> Playback<T> {
+    /// The playback state of a Room which never started playback
+    pub fn stopped(dal: T, room_uuid: Uuid) -> Self {
+        Self {
+            dal,
+            room_uuid,
+            track_uuid: None,
+            status: PlaybackStatus::Stopped,
+            position: 0,
+            reference_time: 0,
+        }
+    }
+
+    /// The position into the Track in milliseconds at the provided UNIX timestamp in milliseconds.
+    /// Before the reference time, playback has not started yet
+    pub fn position_at(&self, time: i64) -> i64 {
+        match self.status {
+            PlaybackStatus::Playing => self.position + (time - self.reference_time).max(0),
+            _ => self.position,
+        }
+    }
+}
+
+impl Playback<Mysql> {
+    /// Get the playback state of a Room. A Room which never started playback is stopped
+    pub(crate) fn get(dal: Mysql, room_uuid: Uuid) -> DalResult<Self> {
+        let mut conn = dal.get_conn()?;
+        let row = match conn.exec_first::<Row, &str, Params>("SELECT track_uuid,status,position,reference_time FROM playback WHERE room_uuid = :room_uuid", params! {
+            "room_uuid" => &room_uuid
+        })? {
+            Some(x) => x,
+            None => return Ok(Self::stopped(dal, room_uuid))
+        };
+
+        let status: String = row.get("status").unwrap();
+        let status = PlaybackStatus::new(&status)
+            .ok_or(Error::Other(format!("Unknown playback status '{status}'")))?;
+
+        Ok(Self {
+            dal,
+            room_uuid,
+            track_uuid: row.get("track_uuid").unwrap(),
+            status,
+            position: row.get("position").unwrap(),
+            reference_time: row.get("reference_time").unwrap(),
+        })
+    }
+
+    pub fn save(&self) -> DalResult<()> {
+        let mut conn = self.dal.get_conn()?;
+        conn.exec_drop("INSERT INTO playback (room_uuid, track_uuid, status, position, reference_time) VALUES (:room_uuid, :track_uuid, :status, :position, :reference_time) ON DUPLICATE KEY UPDATE track_uuid = VALUES(track_uuid), status = VALUES(status), position = VALUES(position), reference_time = VALUES(reference_time)", params! {
+            "room_uuid" => &self.room_uuid,
+            "track_uuid" => &self.track_uuid,
+            "status" => self.status.to_string(),
+            "position" => self.position,
+            "reference_time" => self.reference_time,
+        })?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct TestStore;
+
+    impl Datastore for TestStore {}
+
+    fn playback(status: PlaybackStatus, position: i64, reference_time: i64) -> Playback<TestStore> {
+        Playback {
+            status,
+            position,
+            reference_time,
+            ..Playback::stopped(TestStore, Uuid::new_v4())
+        }
+    }
+
+    #[test]
+    fn position_at_playing() {
+        let playback = playback(PlaybackStatus::Playing, 10_000, 50_000);
+        assert_eq!(playback.position_at(50_000), 10_000);
+        assert_eq!(playback.position_at(52_500), 12_500);
+    }
+
+    #[test]
+    fn position_at_before_reference_time() {
+        // Playback is scheduled to start in the future, so devices can buffer
+        let playback = playback(PlaybackStatus::Playing, 0, 50_000);
+        assert_eq!(playback.position_at(48_000), 0);
+    }
+
+    #[test]
+    fn position_at_paused() {
+        let playback = playback(PlaybackStatus::Paused, 10_000, 50_000);
+        assert_eq!(playback.position_at(60_000), 10_000);
+    }
+
+    #[test]
+    fn position_at_stopped() {
+        let playback = playback(PlaybackStatus::Stopped, 0, 50_000);
+        assert_eq!(playback.position_at(60_000), 0);
+    }
+}
@@ -0,0 +1,144 @@
+use mysql::prelude::Queryable;
+use mysql::{params, TxOpts};
+use mysql_common::row::Row;
+use crate::{uuid::Uuid, Dal, DalResult, Datastore, Mysql};
+
+/// A client which plays the audio of a Room.
+/// At most one device per Room is active, the others are on standby
+pub struct PlayerDevice<T: Datastore> {
+    dal: T,
+    pub uuid: Uuid,
+    pub room_uuid: Uuid,
+    /// The User operating the device
+    pub user_uuid: Uuid,
+    pub name: String,
+    pub registered_at: i64,
+    /// UNIX timestamp of the last heartbeat received from the device
+    pub last_heartbeat: i64,
+    pub active: bool,
+}
+
+pub struct PlayerDeviceBuildable {
+    pub room_uuid: Uuid,
+    pub user_uuid: Uuid,
+    pub name: String,
+}
+
+pub trait PlayerDeviceExt<T: Datastore, U>: Dal<T, U> {
+    /// Make this the active device of its Room, putting all other devices on standby
+    fn activate(&mut self) -> DalResult<()>;
+    /// List all active devices whose last heartbeat was before the provided UNIX timestamp
+    fn list_active_heartbeat_before(dal: T, timestamp: i64) -> DalResult<Vec<Self>>;
+    /// Delete all devices whose last heartbeat was before the provided UNIX timestamp.
+    /// Returns the amount of devices deleted
+    fn delete_heartbeat_before(dal: T, timestamp: i64) -> DalResult<u64>;
+}
+
+impl Dal<Mysql, PlayerDeviceBuildable> for PlayerDevice<Mysql> {
+    fn get(dal: Mysql, uuid: Uuid) -> DalResult<Option<Self>> {
+        let mut conn = dal.get_conn()?;
+        let row: Row = match conn.exec_first("SELECT room_uuid,user_uuid,name,registered_at,last_heartbeat,active FROM player_devices WHERE uuid = :uuid", params! {
+            "uuid" => &uuid
+        })? {
+            Some(x) => x,
+            None => return Ok(None)
+        };
+
+        Ok(Some(Self {
+            dal,
+            uuid,
+            room_uuid: row.get("room_uuid").unwrap(),
+            user_uuid: row.get("user_uuid").unwrap(),
+            name: row.get("name").unwrap(),
+            registered_at: row.get("registered_at").unwrap(),
+            last_heartbeat: row.get("last_heartbeat").unwrap(),
+            active: row.get("active").unwrap(),
+        }))
+    }
+
+    fn delete(self) -> DalResult<()> {
+        let mut conn = self.dal.get_conn()?;
+        conn.exec_drop("DELETE FROM player_devices WHERE uuid = :uuid", params! {
+            "uuid" => &self.uuid
+        })?;
+
+        Ok(())
+    }
+
+    fn update(&mut self) -> DalResult<()> {
+        let mut conn = self.dal.get_conn()?;
+        conn.exec_drop("UPDATE player_devices SET name = :name, last_heartbeat = :last_heartbeat, active = :active WHERE uuid = :uuid", params! {
+            "name" => &self.name,
+            "last_heartbeat" => self.last_heartbeat,
+            "active" => self.active,
+            "uuid" => &self.uuid,
+        })?;
+
+        Ok(())
+    }
+
+    fn create(dal: Mysql, buildable: PlayerDeviceBuildable) -> DalResult<Self> {
+        let mut conn = dal.get_conn()?;
+        let uuid = Uuid::new_v4();
+        let now = time::OffsetDateTime::now_utc().unix_timestamp();
+        conn.exec_drop("INSERT INTO player_devices (uuid, room_uuid, user_uuid, name, registered_at, last_heartbeat, active) VALUES (:uuid, :room_uuid, :user_uuid, :name, :registered_at, :last_heartbeat, FALSE)", params! {
+            "uuid" => &uuid,
+            "room_uuid" => &buildable.room_uuid,
+            "user_uuid" => &buildable.user_uuid,
+            "name" => &buildable.name,
+            "registered_at" => now,
+            "last_heartbeat" => now,
+        })?;
+
+        Ok(Self {
+            dal,
+            uuid,
+            room_uuid: buildable.room_uuid,
+            user_uuid: buildable.user_uuid,
+            name: buildable.name,
+            registered_at: now,
+            last_heartbeat: now,
+            active: false,
+        })
+    }
+}
+
+impl PlayerDeviceExt<Mysql, PlayerDeviceBuildable> for PlayerDevice<Mysql> {
+    fn activate(&mut self) -> DalResult<()> {
+        let mut tx = self.dal.start_transaction(TxOpts::default())?;
+        tx.exec_drop("UPDATE player_devices SET active = (uuid = :uuid) WHERE room_uuid = :room_uuid", params! {
+            "uuid" => &self.uuid,
+            "room_uuid" => &self.room_uuid,
+        })?;
+        tx.commit()?;
+
+        self.active = true;
+        Ok(())
+    }
+
+    fn list_active_heartbeat_before(dal: Mysql, timestamp: i64) -> DalResult<Vec<Self>> {
+        let mut conn = dal.get_conn()?;
+        let rows: Vec<Row> = conn.exec("SELECT uuid FROM player_devices WHERE active = TRUE AND last_heartbeat < :timestamp", params! {
+            "timestamp" => timestamp
+        })?;
+
+        let devices = rows.into_iter()
+            .map(|x| x.get::<Uuid, &str>("uuid").unwrap())
+            .map(|x| Self::get(dal.clone(), x))
+            .collect::<DalResult<Vec<_>>>()?
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+
+        Ok(devices)
+    }
+
+    fn delete_heartbeat_before(dal: Mysql, timestamp: i64) -> DalResult<u64> {
+        let mut conn = dal.get_conn()?;
+        conn.exec_drop("DELETE FROM player_devices WHERE last_heartbeat < :timestamp", params! {
+            "timestamp" => timestamp
+        })?;
+
+        Ok(conn.affected_rows())
+    }
+}
@@ -36,6 +36,12 @@ impl ToString for PretrackSourcePlatform {
     }
 }
 
+pub trait PretrackExt<T: Datastore, U>: Dal<T, U> {
+    /// Delete all Pretracks created before the provided UNIX timestamp.
+    /// Returns the amount of Pretracks deleted
+    fn delete_created_before(dal: T, timestamp: i64) -> DalResult<u64>;
+}
+
 pub struct Pretrack<T: Datastore> {
     dal: T,
     pub uuid: Uuid,
@@ -43,13 +49,18 @@ pub struct Pretrack<T: Datastore> {
     pub artist: String,
     pub duration: i64,
     pub thumbnail_url: String,
-    pub platform: PretrackSourcePlatform
+    pub platform: PretrackSourcePlatform,
+    pub created_at: i64,
+    /// The Room in which the Pretrack was looked up
+    pub room_uuid: Uuid,
+    /// The User who looked up the Pretrack
+    pub user_uuid: Uuid,
 }
 
 impl Dal<Mysql, PretrackBuildable> for Pretrack<Mysql> {
     fn get(dal: Mysql, uuid: Uuid) -> DalResult<Option<Self>> {
         let mut conn = dal.get_conn()?;
-        let row: Row = match conn.exec_first("SELECT name,artist,duration,thumbnail_url,platform,platform_track_id FROM pretracks WHERE uuid = :uuid", params! {
+        let row: Row = match conn.exec_first("SELECT name,artist,duration,thumbnail_url,platform,platform_track_id,created_at,room_uuid,user_uuid FROM pretracks WHERE uuid = :uuid", params! {
             "uuid" => &uuid
         })? {
             Some(x) => x,
@@ -69,6 +80,9 @@ impl Dal<Mysql, PretrackBuildable> for Pretrack<Mysql> {
             duration: row.get("duration").unwrap(),
             thumbnail_url: row.get("thumbnail_url").unwrap(),
             platform,
+            created_at: row.get("created_at").unwrap(),
+            room_uuid: row.get("room_uuid").unwrap(),
+            user_uuid: row.get("user_uuid").unwrap(),
         }))
     }
 
@@ -88,7 +102,8 @@ impl Dal<Mysql, PretrackBuildable> for Pretrack<Mysql> {
     fn create(dal: Mysql, buildable: PretrackBuildable) -> DalResult<Self> {
         let mut conn = dal.get_conn()?;
         let uuid = Uuid::new_v4();
-        conn.exec_drop("INSERT INTO pretracks (uuid, name, artist, duration, thumbnail_url, platform, platform_track_id) VALUES (:uuid, :name, :artist, :duration, :thumbnail_url, :platform, :platform_track_id)", params! {
+        let created_at = time::OffsetDateTime::now_utc().unix_timestamp();
+        conn.exec_drop("INSERT INTO pretracks (uuid, name, artist, duration, thumbnail_url, platform, platform_track_id, created_at, room_uuid, user_uuid) VALUES (:uuid, :name, :artist, :duration, :thumbnail_url, :platform, :platform_track_id, :created_at, :room_uuid, :user_uuid)", params! {
             "uuid" => &uuid,
             "name" => &buildable.name,
             "artist" => &buildable.artist,
@@ -96,6 +111,9 @@ impl Dal<Mysql, PretrackBuildable> for Pretrack<Mysql> {
             "thumbnail_url" => &buildable.thumbnail_url,
             "platform" => buildable.platform.to_string(),
             "platform_track_id" => buildable.platform.get_track_id(),
+            "created_at" => created_at,
+            "room_uuid" => &buildable.room_uuid,
+            "user_uuid" => &buildable.user_uuid,
         })?;
 
         Ok(Self {
@@ -105,15 +123,31 @@ impl Dal<Mysql, PretrackBuildable> for Pretrack<Mysql> {
             artist: buildable.artist,
             duration: buildable.duration,
             thumbnail_url: buildable.thumbnail_url,
-            platform: buildable.platform
+            platform: buildable.platform,
+            created_at,
+            room_uuid: buildable.room_uuid,
+            user_uuid: buildable.user_uuid,
         })
     }
 }
 
+impl PretrackExt<Mysql, PretrackBuildable> for Pretrack<Mysql> {
+    fn delete_created_before(dal: Mysql, timestamp: i64) -> DalResult<u64> {
+        let mut conn = dal.get_conn()?;
+        conn.exec_drop("DELETE FROM pretracks WHERE created_at < :timestamp", params! {
+            "timestamp" => timestamp
+        })?;
+
+        Ok(conn.affected_rows())
+    }
+}
+
 pub struct PretrackBuildable {
     pub name: String,
     pub artist: String,
     pub duration: i64,
     pub thumbnail_url: String,
     pub platform: PretrackSourcePlatform,
+    pub room_uuid: Uuid,
+    pub user_uuid: Uuid,
 }
\ No newline at end of file
@@ -0,0 +1,133 @@
+use mysql::prelude::Queryable;
+use mysql_common::params;
+use mysql_common::row::Row;
+use uuid::Uuid;
+use crate::{Dal, DalResult, Datastore, Mysql};
+
+/// A Web Push subscription of a User's browser
+pub struct PushSubscription<T: Datastore> {
+    dal: T,
+    pub uuid: Uuid,
+    pub user_uuid: Uuid,
+    /// The URL of the push service to which messages for this subscription are sent
+    pub endpoint: String,
+    /// The public key of the browser, base64url encoded
+    pub p256dh: String,
+    /// The authentication secret of the browser, base64url encoded
+    pub auth: String,
+    pub created_at: i64,
+}
+
+pub struct PushSubscriptionBuildable {
+    pub user_uuid: Uuid,
+    pub endpoint: String,
+    pub p256dh: String,
+    pub auth: String,
+}
+
+/// The maximum length of the endpoint of a push subscription
+pub const MAX_PUSH_ENDPOINT_LENGTH: usize = 1024;
+/// The maximum length of the keys of a push subscription
+pub const MAX_PUSH_KEY_LENGTH: usize = 256;
+
+pub trait PushSubscriptionExt<T: Datastore, U>: Dal<T, U> {
+    /// List all subscriptions of a User
+    fn list(dal: T, user_uuid: Uuid) -> DalResult<Vec<Self>>;
+    /// Get the subscription of a User with the provided endpoint
+    fn get_by_endpoint(dal: T, user_uuid: Uuid, endpoint: &str) -> DalResult<Option<Self>>;
+}
+
+impl Dal<Mysql, PushSubscriptionBuildable> for PushSubscription<Mysql> {
+    fn get(dal: Mysql, uuid: Uuid) -> DalResult<Option<Self>> {
+        let mut conn = dal.get_conn()?;
+        let row: Row = match conn.exec_first("SELECT user_uuid,endpoint,p256dh,auth,created_at FROM push_subscriptions WHERE uuid = :uuid", params! {
+            "uuid" => &uuid
+        })? {
+            Some(x) => x,
+            None => return Ok(None)
+        };
+
+        Ok(Some(Self {
+            dal,
+            uuid,
+            user_uuid: row.get("user_uuid").unwrap(),
+            endpoint: row.get("endpoint").unwrap(),
+            p256dh: row.get("p256dh").unwrap(),
+            auth: row.get("auth").unwrap(),
+            created_at: row.get("created_at").unwrap(),
+        }))
+    }
+
+    fn delete(self) -> DalResult<()> {
+        let mut conn = self.dal.get_conn()?;
+        conn.exec_drop("DELETE FROM push_subscriptions WHERE uuid = :uuid", params! {
+            "uuid" => &self.uuid
+        })?;
+
+        Ok(())
+    }
+
+    fn update(&mut self) -> DalResult<()> {
+        let mut conn = self.dal.get_conn()?;
+        conn.exec_drop("UPDATE push_subscriptions SET p256dh = :p256dh, auth = :auth WHERE uuid = :uuid", params! {
+            "p256dh" => &self.p256dh,
+            "auth" => &self.auth,
+            "uuid" => &self.uuid,
+        })?;
+
+        Ok(())
+    }
+
+    fn create(dal: Mysql, buildable: PushSubscriptionBuildable) -> DalResult<Self> {
+        let mut conn = dal.get_conn()?;
+        let uuid = Uuid::new_v4();
+        let created_at = time::OffsetDateTime::now_utc().unix_timestamp();
+        conn.exec_drop("INSERT INTO push_subscriptions (uuid, user_uuid, endpoint, p256dh, auth, created_at) VALUES (:uuid, :user_uuid, :endpoint, :p256dh, :auth, :created_at)", params! {
+            "uuid" => &uuid,
+            "user_uuid" => &buildable.user_uuid,
+            "endpoint" => &buildable.endpoint,
+            "p256dh" => &buildable.p256dh,
+            "auth" => &buildable.auth,
+            "created_at" => created_at,
+        })?;
+
+        Ok(Self {
+            dal,
+            uuid,
+            user_uuid: buildable.user_uuid,
+            endpoint: buildable.endpoint,
+            p256dh: buildable.p256dh,
+            auth: buildable.auth,
+            created_at,
+        })
+    }
+}
+
+impl PushSubscriptionExt<Mysql, PushSubscriptionBuildable> for PushSubscription<Mysql> {
+    fn list(dal: Mysql, user_uuid: Uuid) -> DalResult<Vec<Self>> {
+        let mut conn = dal.get_conn()?;
+        let rows: Vec<Row> = conn.exec("SELECT uuid,endpoint,p256dh,auth,created_at FROM push_subscriptions WHERE user_uuid = :user_uuid ORDER BY id", params! {
+            "user_uuid" => &user_uuid
+        })?;
+
+        let subscriptions = rows.into_iter()
+            .map(|row| Self {
+                dal: dal.clone(),
+                uuid: row.get("uuid").unwrap(),
+                user_uuid,
+                endpoint: row.get("endpoint").unwrap(),
+                p256dh: row.get("p256dh").unwrap(),
+                auth: row.get("auth").unwrap(),
+                created_at: row.get("created_at").unwrap(),
+            })
+            .collect();
+
+        Ok(subscriptions)
+    }
+
+    fn get_by_endpoint(dal: Mysql, user_uuid: Uuid, endpoint: &str) -> DalResult<Option<Self>> {
+        Ok(Self::list(dal, user_uuid)?
+            .into_iter()
+            .find(|x| x.endpoint.eq(endpoint)))
+    }
+}
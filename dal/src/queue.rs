@@ -1,9 +1,86 @@
 use mysql::prelude::Queryable;
+use mysql::{Transaction, TxOpts};
 use mysql_common::params;
 use mysql_common::params::Params;
 use mysql_common::row::Row;
+use thiserror::Error;
 use uuid::Uuid;
-use crate::{DalResult, Datastore, Mysql, Track, Dal};
+use crate::{DalResult, Datastore, Mysql, Pretrack, Track, TrackBuildable, Dal};
+
+/// The state of a Track in the queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueueStatus {
+    Enqueued,
+    /// The Track awaits approval by a moderator of the Room
+    Pending,
+}
+
+impl QueueStatus {
+    pub(crate) fn new(status: &str) -> Option<Self> {
+        match status {
+            "Enqueued" => Some(Self::Enqueued),
+            "Pending" => Some(Self::Pending),
+            _ => None
+        }
+    }
+}
+
+impl ToString for QueueStatus {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Enqueued => "Enqueued".into(),
+            Self::Pending => "Pending".into(),
+        }
+    }
+}
+
+/// Limits on how many Tracks a User may add to the queue
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AddLimits {
+    /// The maximum amount of Tracks a User may have in the queue which are not playing or played yet
+    pub max_pending: Option<u32>,
+    /// The minimum time in seconds between two Tracks added by a User
+    pub cooldown: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum LimitExceeded {
+    #[error("You already have {limit} track(s) waiting to be played")]
+    TooManyPending {
+        limit: u32,
+    },
+    #[error("You can add another track in {retry_after} second(s)")]
+    Cooldown {
+        /// The remaining cooldown in seconds
+        retry_after: u64,
+    },
+}
+
+pub enum EnqueueStatus {
+    Ok {
+        idx: i64,
+    },
+    LimitExceeded(LimitExceeded),
+}
+
+pub enum EnqueuePretrackStatus {
+    Ok {
+        track: Track<Mysql>,
+        idx: i64,
+    },
+    /// Nothing was created, the Pretrack still exists
+    LimitExceeded(LimitExceeded),
+}
+
+/// The position and origin of a Track in the queue
+#[derive(Debug, Clone, Copy)]
+pub struct QueueEntry {
+    pub idx: i64,
+    pub added_by: Uuid,
+    /// UNIX timestamp at which the Track was added
+    pub added_at: i64,
+    pub status: QueueStatus,
+}
 
 pub struct Queue<T: Datastore> {
     pub(crate) dal: T,
@@ -16,12 +93,38 @@ struct IndexedTrack<T: Datastore> {
 }
 
 impl Queue<Mysql> {
+    /// Get the Tracks which are enqueued, in order. Pending Tracks are not included
     pub fn get_enqueued(&self) -> DalResult<Vec<Track<Mysql>>> {
+        self.get_with_status(QueueStatus::Enqueued)
+    }
+
+    /// Get the Tracks which await approval, in the order they were added
+    pub fn get_pending(&self) -> DalResult<Vec<Track<Mysql>>> {
+        self.get_with_status(QueueStatus::Pending)
+    }
+
+    fn get_with_status(&self, status: QueueStatus) -> DalResult<Vec<Track<Mysql>>> {
         let mut conn = self.dal.get_conn()?;
-        let rows: Vec<Row> = conn.exec("SELECT track_uuid,idx FROM queue WHERE room_uuid = :room_uuid", params! {
-            "room_uuid" => &self.room_uuid
+        let rows: Vec<Row> = conn.exec("SELECT track_uuid,idx FROM queue WHERE room_uuid = :room_uuid AND status = :status", params! {
+            "room_uuid" => &self.room_uuid,
+            "status" => status.to_string()
+        })?;
+
+        self.get_ordered(rows)
+    }
+
+    /// Get the Tracks added by a User, both enqueued and pending, in order
+    pub fn get_added_by(&self, added_by: &Uuid) -> DalResult<Vec<Track<Mysql>>> {
+        let mut conn = self.dal.get_conn()?;
+        let rows: Vec<Row> = conn.exec("SELECT track_uuid,idx FROM queue WHERE room_uuid = :room_uuid AND added_by = :added_by", params! {
+            "room_uuid" => &self.room_uuid,
+            "added_by" => added_by
         })?;
 
+        self.get_ordered(rows)
+    }
+
+    fn get_ordered(&self, rows: Vec<Row>) -> DalResult<Vec<Track<Mysql>>> {
         let mut tracks = rows.into_iter()
             .map(|x| {
                 (x.get::<Uuid, &str>("track_uuid").unwrap(), x.get::<i64, &str>("idx").unwrap())
@@ -41,22 +144,155 @@ impl Queue<Mysql> {
     }
 
     pub fn enqueue(&self, track: &Track<Mysql>, added_by: &Uuid) -> DalResult<i64> {
+        match self.insert(added_by, QueueStatus::Enqueued, AddLimits::default(), |_| Ok(track.uuid))? {
+            EnqueueStatus::Ok { idx } => Ok(idx),
+            EnqueueStatus::LimitExceeded(_) => unreachable!(),
+        }
+    }
+
+    /// Turn a Pretrack into a Track and add it, unless the User exceeds the limits.
+    /// Adds to the same Room are serialized, so concurrent adds can not exceed the limits.
+    /// Both happen in one transaction, so the Pretrack is kept if the limits are exceeded
+    pub fn enqueue_pretrack(&self, pretrack: Pretrack<Mysql>, added_by: &Uuid, status: QueueStatus, limits: AddLimits) -> DalResult<EnqueuePretrackStatus> {
+        let mut track = None;
+        let status = self.insert(added_by, status, limits, |tx| {
+            let created = Track::insert(tx, self.dal.clone(), TrackBuildable {
+                room_uuid: self.room_uuid,
+                pretrack
+            })?;
+            let uuid = created.uuid;
+            track = Some(created);
+            Ok(uuid)
+        })?;
+
+        Ok(match status {
+            EnqueueStatus::Ok { idx } => EnqueuePretrackStatus::Ok {
+                track: track.unwrap(),
+                idx
+            },
+            EnqueueStatus::LimitExceeded(exceeded) => EnqueuePretrackStatus::LimitExceeded(exceeded),
+        })
+    }
+
+    /// Check whether the User currently exceeds the limits, without adding anything
+    pub fn check_limits(&self, added_by: &Uuid, limits: AddLimits) -> DalResult<Option<LimitExceeded>> {
         let mut conn = self.dal.get_conn()?;
-        let idx = match conn.exec_first::<Row, &str, Params>("SELECT idx FROM queue WHERE room_uuid = :room_uuid ORDER BY idx DESC", params! {
+        self.limit_exceeded(&mut conn, added_by, limits)
+    }
+
+    /// `track` is called within the transaction once the limits are checked, and returns the UUID of the Track to add
+    fn insert<F>(&self, added_by: &Uuid, status: QueueStatus, limits: AddLimits, track: F) -> DalResult<EnqueueStatus>
+    where
+        F: FnOnce(&mut Transaction) -> DalResult<Uuid>
+    {
+        let mut tx = self.dal.start_transaction(TxOpts::default())?;
+        // Lock the Room, so concurrent adds see each other's Tracks
+        tx.exec_drop("SELECT id FROM rooms WHERE uuid = :room_uuid FOR UPDATE", params! {
+            "room_uuid" => &self.room_uuid
+        })?;
+
+        if let Some(exceeded) = self.limit_exceeded(&mut tx, added_by, limits)? {
+            tx.rollback()?;
+            return Ok(EnqueueStatus::LimitExceeded(exceeded));
+        }
+
+        let track_uuid = track(&mut tx)?;
+        let idx = match tx.exec_first::<Row, &str, Params>("SELECT idx FROM queue WHERE room_uuid = :room_uuid ORDER BY idx DESC", params! {
             "room_uuid" => &self.room_uuid
         })? {
             Some(x) => x.get("idx").unwrap(),
             None => 0_i64
         };
 
-        conn.exec_drop("INSERT INTO queue (track_uuid, room_uuid, idx, added_by) VALUES (:track_uuid, :room_uuid, :idx, :added_by)", params! {
-            "track_uuid" => &track.uuid,
+        let now = time::OffsetDateTime::now_utc().unix_timestamp();
+        tx.exec_drop("INSERT INTO queue (track_uuid, room_uuid, idx, added_by, status, added_at) VALUES (:track_uuid, :room_uuid, :idx, :added_by, :status, :added_at)", params! {
+            "track_uuid" => &track_uuid,
             "room_uuid" => &self.room_uuid,
             "idx" => idx + 1,
-            "added_by" => added_by
+            "added_by" => added_by,
+            "status" => status.to_string(),
+            "added_at" => now
         })?;
 
-        Ok(idx + 1)
+        // The cooldown is based on this, rather than the queue, as Tracks may be removed from the queue
+        tx.exec_drop("UPDATE room_members SET last_added_at = :now WHERE room_uuid = :room_uuid AND user_uuid = :user_uuid", params! {
+            "now" => now,
+            "room_uuid" => &self.room_uuid,
+            "user_uuid" => added_by
+        })?;
+        tx.commit()?;
+
+        Ok(EnqueueStatus::Ok {
+            idx: idx + 1
+        })
+    }
+
+    fn limit_exceeded<Q: Queryable>(&self, conn: &mut Q, added_by: &Uuid, limits: AddLimits) -> DalResult<Option<LimitExceeded>> {
+        if let Some(limit) = limits.max_pending {
+            let row = conn.exec_first::<Row, &str, Params>("SELECT COUNT(*) AS pending FROM queue WHERE room_uuid = :room_uuid AND added_by = :added_by AND track_uuid NOT IN (SELECT track_uuid FROM playback WHERE room_uuid = :room_uuid AND track_uuid IS NOT NULL)", params! {
+                "room_uuid" => &self.room_uuid,
+                "added_by" => added_by
+            })?;
+
+            let pending = row.map(|x| x.get::<i64, &str>("pending").unwrap()).unwrap_or(0);
+            if pending >= limit as i64 {
+                return Ok(Some(LimitExceeded::TooManyPending {
+                    limit
+                }));
+            }
+        }
+
+        if let Some(cooldown) = limits.cooldown {
+            let row = conn.exec_first::<Row, &str, Params>("SELECT last_added_at FROM room_members WHERE room_uuid = :room_uuid AND user_uuid = :user_uuid", params! {
+                "room_uuid" => &self.room_uuid,
+                "user_uuid" => added_by
+            })?;
+
+            let last_added = row.and_then(|x| x.get::<Option<i64>, &str>("last_added_at").unwrap());
+            if let Some(last_added) = last_added {
+                let elapsed = time::OffsetDateTime::now_utc().unix_timestamp() - last_added;
+                if elapsed < cooldown as i64 {
+                    return Ok(Some(LimitExceeded::Cooldown {
+                        retry_after: (cooldown as i64 - elapsed) as u64
+                    }));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Enqueue a pending Track. It is moved to the end of the queue.
+    /// Returns `None` if the Track is no longer pending, e.g. because it was approved or rejected concurrently
+    pub fn approve(&self, track: &Track<Mysql>) -> DalResult<Option<i64>> {
+        let mut tx = self.dal.start_transaction(TxOpts::default())?;
+        // Lock the Room, so concurrent approvals and adds see each other's changes
+        tx.exec_drop("SELECT id FROM rooms WHERE uuid = :room_uuid FOR UPDATE", params! {
+            "room_uuid" => &self.room_uuid
+        })?;
+
+        let idx = match tx.exec_first::<Row, &str, Params>("SELECT idx FROM queue WHERE room_uuid = :room_uuid ORDER BY idx DESC FOR UPDATE", params! {
+            "room_uuid" => &self.room_uuid
+        })? {
+            Some(x) => x.get("idx").unwrap(),
+            None => 0_i64
+        };
+
+        tx.exec_drop("UPDATE queue SET status = :status, idx = :idx WHERE room_uuid = :room_uuid AND track_uuid = :track_uuid AND status = :pending", params! {
+            "status" => QueueStatus::Enqueued.to_string(),
+            "idx" => idx + 1,
+            "room_uuid" => &self.room_uuid,
+            "track_uuid" => &track.uuid,
+            "pending" => QueueStatus::Pending.to_string()
+        })?;
+
+        if tx.affected_rows() == 0 {
+            tx.rollback()?;
+            return Ok(None);
+        }
+        tx.commit()?;
+
+        Ok(Some(idx + 1))
     }
 
     pub fn dequeue(&self, track: &Track<Mysql>) -> DalResult<()> {
@@ -3,11 +3,13 @@ use mysql::TxOpts;
 use mysql_common::params;
 use mysql_common::row::Row;
 use rand::Rng;
-use crate::{uuid::Uuid, Dal, DalResult, Datastore, Mysql, Track, Queue};
+use crate::{uuid::Uuid, AddLimits, Dal, DalResult, Datastore, Error, Mysql, Track, Queue, Playback, PlayerDevice, SkipVotes};
 
 pub struct Member {
     pub uuid: Uuid,
     pub joined_at: i64,
+    /// Co-hosts may moderate the Room alongside its owner
+    pub co_host: bool,
 }
 
 pub enum RemoveStatus {
@@ -17,13 +19,68 @@ pub enum RemoveStatus {
     LastMember
 }
 
+/// The amount of votes required to skip a Track
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipThreshold {
+    /// A fixed amount of votes
+    Absolute(u32),
+    /// A percentage of the members of the Room
+    Percentage(u32),
+}
+
+impl SkipThreshold {
+    fn new(kind: &str, value: u32) -> Option<Self> {
+        match kind {
+            "Absolute" => Some(Self::Absolute(value)),
+            "Percentage" => Some(Self::Percentage(value)),
+            _ => None
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Absolute(_) => "Absolute",
+            Self::Percentage(_) => "Percentage",
+        }
+    }
+
+    fn value(&self) -> u32 {
+        match self {
+            Self::Absolute(x) | Self::Percentage(x) => *x,
+        }
+    }
+
+    /// The amount of votes required in a Room with the provided amount of present members.
+    /// Never more than the amount of members, and always at least one
+    pub fn required(&self, members: usize) -> u32 {
+        let required = match self {
+            Self::Absolute(x) => (*x).min(members as u32),
+            Self::Percentage(x) => (members as u32 * x + 99) / 100,
+        };
+
+        required.max(1)
+    }
+}
+
 pub trait RoomExt<T: Datastore, U>: Dal<T, U> {
     fn get_by_join_code<S: AsRef<str>>(dal: T, code: S) -> DalResult<Option<Self>>;
-    fn add_user(&mut self, user: &Uuid) -> DalResult<()>;
+    /// Add a User to the Room. Returns false if the User already is a member
+    fn add_user(&mut self, user: &Uuid) -> DalResult<bool>;
     fn remove_user(&mut self, user: &Uuid) -> DalResult<RemoveStatus>;
     fn list_members(&self) -> DalResult<Vec<Member>>;
+    fn set_co_host(&self, user: &Uuid, co_host: bool) -> DalResult<()>;
+    /// Whether the User is the owner or a co-host of the Room
+    fn is_moderator(&self, user: &Uuid) -> DalResult<bool>;
     fn list_tracks(&self) -> DalResult<Vec<Track<T>>>;
     fn get_queue(&self) -> DalResult<Queue<T>>;
+    fn get_playback(&self) -> DalResult<Playback<T>>;
+    fn get_skip_votes(&self, track_uuid: Uuid) -> DalResult<SkipVotes<T>>;
+    /// List the player devices registered in the Room, in order of registration
+    fn list_player_devices(&self) -> DalResult<Vec<PlayerDevice<T>>>;
+    /// List the Tracks of the Room which are no longer enqueued, most recently added first
+    fn list_history(&self, limit: usize) -> DalResult<Vec<Track<T>>>;
+    /// List all Rooms with autoplay enabled
+    fn list_autoplay(dal: T) -> DalResult<Vec<Self>>;
 }
 
 pub struct Room<T: Datastore> {
@@ -32,11 +89,33 @@ pub struct Room<T: Datastore> {
     pub owner: Uuid,
     pub name: String,
     pub join_code: String,
+    /// ISO 3166-1 alpha-2 code of the region the Room plays in
+    pub region_code: Option<String>,
+    /// Whether the queue should be filled automatically when it runs dry
+    pub autoplay: bool,
+    pub skip_threshold: SkipThreshold,
+    /// Whether Tracks added by members must be approved by a moderator before they are enqueued
+    pub require_approval: bool,
+    /// The maximum amount of Tracks a member may have in the queue which are not playing or played yet
+    pub max_pending_per_user: Option<u32>,
+    /// The minimum time in seconds between two Tracks added by a member
+    pub add_cooldown: Option<u64>,
+}
+
+impl<T: Datastore> Room<T> {
+    /// The limits on adding Tracks which apply to members
+    pub fn add_limits(&self) -> AddLimits {
+        AddLimits {
+            max_pending: self.max_pending_per_user,
+            cooldown: self.add_cooldown,
+        }
+    }
 }
 
 pub struct RoomBuildable {
     pub user_owner: Uuid,
     pub name: String,
+    pub region_code: Option<String>,
 }
 
 const JOIN_CODE_LENGTH: usize = 6;
@@ -48,7 +127,7 @@ pub fn generate_join_code() -> String {
 impl Dal<Mysql, RoomBuildable> for Room<Mysql> {
     fn get(dal: Mysql, uuid: Uuid) -> DalResult<Option<Self>> {
         let mut tx = dal.start_transaction(TxOpts::default())?;
-        let row: Row = match tx.exec_first("SELECT name,owner,join_code FROM rooms WHERE uuid = :uuid", params! {
+        let row: Row = match tx.exec_first("SELECT name,owner,join_code,region_code,autoplay,skip_threshold_kind,skip_threshold,require_approval,max_pending_per_user,add_cooldown FROM rooms WHERE uuid = :uuid", params! {
             "uuid" => &uuid
         })? {
             Some(x) => x,
@@ -58,6 +137,14 @@ impl Dal<Mysql, RoomBuildable> for Room<Mysql> {
         let name: String = row.get("name").unwrap();
         let owner: Uuid = row.get("owner").unwrap();
         let join_code: String = row.get("join_code").unwrap();
+        let region_code: Option<String> = row.get("region_code").unwrap();
+        let autoplay: bool = row.get("autoplay").unwrap();
+        let skip_threshold_kind: String = row.get("skip_threshold_kind").unwrap();
+        let skip_threshold = SkipThreshold::new(&skip_threshold_kind, row.get("skip_threshold").unwrap())
+            .ok_or(Error::Other(format!("Unknown skip threshold kind '{skip_threshold_kind}'")))?;
+        let require_approval: bool = row.get("require_approval").unwrap();
+        let max_pending_per_user: Option<u32> = row.get("max_pending_per_user").unwrap();
+        let add_cooldown: Option<u64> = row.get("add_cooldown").unwrap();
 
         Ok(Some(Self {
             dal,
@@ -65,6 +152,12 @@ impl Dal<Mysql, RoomBuildable> for Room<Mysql> {
             owner,
             name,
             join_code,
+            region_code,
+            autoplay,
+            skip_threshold,
+            require_approval,
+            max_pending_per_user,
+            add_cooldown,
         }))
     }
 
@@ -79,7 +172,22 @@ impl Dal<Mysql, RoomBuildable> for Room<Mysql> {
     }
 
     fn update(&mut self) -> DalResult<()> {
-        todo!()
+        let mut tx = self.dal.start_transaction(TxOpts::default())?;
+        tx.exec_drop("UPDATE rooms SET name = :name, owner = :owner, region_code = :region_code, autoplay = :autoplay, skip_threshold_kind = :skip_threshold_kind, skip_threshold = :skip_threshold, require_approval = :require_approval, max_pending_per_user = :max_pending_per_user, add_cooldown = :add_cooldown WHERE uuid = :uuid", params! {
+            "name" => &self.name,
+            "owner" => &self.owner,
+            "region_code" => &self.region_code,
+            "autoplay" => self.autoplay,
+            "skip_threshold_kind" => self.skip_threshold.kind(),
+            "skip_threshold" => self.skip_threshold.value(),
+            "require_approval" => self.require_approval,
+            "max_pending_per_user" => self.max_pending_per_user,
+            "add_cooldown" => self.add_cooldown,
+            "uuid" => &self.uuid,
+        })?;
+        tx.commit()?;
+
+        Ok(())
     }
 
     fn create(dal: Mysql, buildable: RoomBuildable) -> DalResult<Self> {
@@ -92,11 +200,12 @@ impl Dal<Mysql, RoomBuildable> for Room<Mysql> {
             join_code = generate_join_code();
         }
 
-        tx.exec_drop("INSERT INTO rooms (name,uuid,owner,join_code) VALUES (:name, :uuid, :owner,:join_code)", params! {
+        tx.exec_drop("INSERT INTO rooms (name,uuid,owner,join_code,region_code) VALUES (:name, :uuid, :owner,:join_code, :region_code)", params! {
             "name" => &buildable.name,
             "uuid" => &uuid,
             "owner" => &buildable.user_owner,
             "join_code" => &join_code,
+            "region_code" => &buildable.region_code,
         })?;
         tx.commit()?;
         Ok(Self {
@@ -105,6 +214,12 @@ impl Dal<Mysql, RoomBuildable> for Room<Mysql> {
             name: buildable.name,
             owner: buildable.user_owner,
             join_code,
+            region_code: buildable.region_code,
+            autoplay: false,
+            skip_threshold: SkipThreshold::Percentage(50),
+            require_approval: false,
+            max_pending_per_user: None,
+            add_cooldown: None,
         })
     }
 }
@@ -123,14 +238,15 @@ impl RoomExt<Mysql, RoomBuildable> for Room<Mysql> {
         Self::get(dal, uuid)
     }
 
-    fn add_user(&mut self, user: &Uuid) -> DalResult<()> {
+    fn add_user(&mut self, user: &Uuid) -> DalResult<bool> {
         let mut conn = self.dal.get_conn()?;
-        conn.exec_drop("INSERT INTO room_members (room_uuid, user_uuid, joined_at) VALUES (:room_uuid, :user_uuid, :joined_at)", params! {
+        // Existing memberships are left untouched, keeping their join time and co-host status
+        conn.exec_drop("INSERT IGNORE INTO room_members (room_uuid, user_uuid, joined_at) VALUES (:room_uuid, :user_uuid, :joined_at)", params! {
             "room_uuid" => &self.uuid,
             "user_uuid" => user,
             "joined_at" => time::OffsetDateTime::now_utc().unix_timestamp()
         })?;
-        Ok(())
+        Ok(conn.affected_rows() > 0)
     }
 
     fn remove_user(&mut self, user: &Uuid) -> DalResult<RemoveStatus> {
@@ -167,7 +283,7 @@ impl RoomExt<Mysql, RoomBuildable> for Room<Mysql> {
 
     fn list_members(&self) -> DalResult<Vec<Member>> {
         let mut conn = self.dal.get_conn()?;
-        let rows: Vec<Row> = conn.exec("SELECT user_uuid,joined_at FROM room_members WHERE room_uuid = :room_uuid", params! {
+        let rows: Vec<Row> = conn.exec("SELECT user_uuid,joined_at,co_host FROM room_members WHERE room_uuid = :room_uuid", params! {
             "room_uuid" => &self.uuid
         })?;
 
@@ -175,11 +291,32 @@ impl RoomExt<Mysql, RoomBuildable> for Room<Mysql> {
             .map(|x| Member {
                 uuid: x.get("user_uuid").unwrap(),
                 joined_at: x.get("joined_at").unwrap(),
+                co_host: x.get("co_host").unwrap(),
             })
             .collect::<Vec<_>>();
         Ok(members)
     }
 
+    fn set_co_host(&self, user: &Uuid, co_host: bool) -> DalResult<()> {
+        let mut conn = self.dal.get_conn()?;
+        conn.exec_drop("UPDATE room_members SET co_host = :co_host WHERE room_uuid = :room_uuid AND user_uuid = :user_uuid", params! {
+            "co_host" => co_host,
+            "room_uuid" => &self.uuid,
+            "user_uuid" => user
+        })?;
+        Ok(())
+    }
+
+    fn is_moderator(&self, user: &Uuid) -> DalResult<bool> {
+        if self.owner.eq(user) {
+            return Ok(true);
+        }
+
+        Ok(self.list_members()?
+            .iter()
+            .any(|x| x.uuid.eq(user) && x.co_host))
+    }
+
     fn list_tracks(&self) -> DalResult<Vec<Track<Mysql>>> {
         let mut conn = self.dal.get_conn()?;
         let rows: Vec<Row> = conn.exec("SELECT uuid FROM tracks WHERE room_uuid = :room_uuid", params! {
@@ -200,4 +337,63 @@ impl RoomExt<Mysql, RoomBuildable> for Room<Mysql> {
             room_uuid: self.uuid.clone()
         })
     }
+
+    fn get_playback(&self) -> DalResult<Playback<Mysql>> {
+        Playback::get(self.dal.clone(), self.uuid)
+    }
+
+    fn get_skip_votes(&self, track_uuid: Uuid) -> DalResult<SkipVotes<Mysql>> {
+        Ok(SkipVotes {
+            dal: self.dal.clone(),
+            room_uuid: self.uuid,
+            track_uuid,
+        })
+    }
+
+    fn list_player_devices(&self) -> DalResult<Vec<PlayerDevice<Mysql>>> {
+        let mut conn = self.dal.get_conn()?;
+        let rows: Vec<Row> = conn.exec("SELECT uuid FROM player_devices WHERE room_uuid = :room_uuid ORDER BY registered_at ASC", params! {
+            "room_uuid" => &self.uuid
+        })?;
+
+        let devices = rows.into_iter()
+            .map(|x| x.get::<Uuid, &str>("uuid").unwrap())
+            .map(|x| PlayerDevice::get(self.dal.clone(), x))
+            .collect::<DalResult<Vec<_>>>()?
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+
+        Ok(devices)
+    }
+
+    fn list_history(&self, limit: usize) -> DalResult<Vec<Track<Mysql>>> {
+        let mut conn = self.dal.get_conn()?;
+        let rows: Vec<Row> = conn.exec("SELECT uuid FROM tracks WHERE room_uuid = :room_uuid AND uuid NOT IN (SELECT track_uuid FROM queue WHERE room_uuid = :room_uuid) ORDER BY id DESC LIMIT :limit", params! {
+            "room_uuid" => &self.uuid,
+            "limit" => limit as u64
+        })?;
+
+        let tracks = rows.into_iter()
+            .map(|x| x.get::<Uuid, &str>("uuid").unwrap())
+            .map(|x| Ok(Track::get(self.dal.clone(), x)?.unwrap()))
+            .collect::<DalResult<Vec<_>>>()?;
+
+        Ok(tracks)
+    }
+
+    fn list_autoplay(dal: Mysql) -> DalResult<Vec<Self>> {
+        let mut conn = dal.get_conn()?;
+        let rows: Vec<Row> = conn.exec("SELECT uuid FROM rooms WHERE autoplay = TRUE", ())?;
+
+        let rooms = rows.into_iter()
+            .map(|x| x.get::<Uuid, &str>("uuid").unwrap())
+            .map(|x| Self::get(dal.clone(), x))
+            .collect::<DalResult<Vec<_>>>()?
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+
+        Ok(rooms)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,49 @@
+use mysql::prelude::Queryable;
+use mysql_common::params;
+use mysql_common::params::Params;
+use mysql_common::row::Row;
+use uuid::Uuid;
+use crate::{DalResult, Datastore, Mysql};
+
+/// The votes cast to skip a Track
+pub struct SkipVotes<T: Datastore> {
+    pub(crate) dal: T,
+    pub room_uuid: Uuid,
+    pub track_uuid: Uuid,
+}
+
+impl SkipVotes<Mysql> {
+    /// Cast a vote. Returns `false` if the User already voted to skip the Track
+    pub fn add(&self, user_uuid: &Uuid) -> DalResult<bool> {
+        let mut conn = self.dal.get_conn()?;
+        conn.exec_drop("INSERT IGNORE INTO skip_votes (room_uuid, track_uuid, user_uuid, voted_at) VALUES (:room_uuid, :track_uuid, :user_uuid, :voted_at)", params! {
+            "room_uuid" => &self.room_uuid,
+            "track_uuid" => &self.track_uuid,
+            "user_uuid" => user_uuid,
+            "voted_at" => time::OffsetDateTime::now_utc().unix_timestamp()
+        })?;
+
+        Ok(conn.affected_rows() > 0)
+    }
+
+    pub fn count(&self) -> DalResult<u32> {
+        let mut conn = self.dal.get_conn()?;
+        let row = conn.exec_first::<Row, &str, Params>("SELECT COUNT(*) AS votes FROM skip_votes WHERE room_uuid = :room_uuid AND track_uuid = :track_uuid", params! {
+            "room_uuid" => &self.room_uuid,
+            "track_uuid" => &self.track_uuid
+        })?;
+
+        Ok(row.map(|x| x.get::<i64, &str>("votes").unwrap()).unwrap_or(0) as u32)
+    }
+
+    /// Remove all votes, e.g. once the Track is skipped or has ended
+    pub fn clear(&self) -> DalResult<()> {
+        let mut conn = self.dal.get_conn()?;
+        conn.exec_drop("DELETE FROM skip_votes WHERE room_uuid = :room_uuid AND track_uuid = :track_uuid", params! {
+            "room_uuid" => &self.room_uuid,
+            "track_uuid" => &self.track_uuid
+        })?;
+
+        Ok(())
+    }
+}
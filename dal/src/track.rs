@@ -2,7 +2,7 @@ use mysql::prelude::Queryable;
 use mysql_common::params;
 use mysql_common::row::Row;
 use uuid::Uuid;
-use crate::{Dal, DalResult, Datastore, Error, Mysql, Pretrack, PretrackSourcePlatform};
+use crate::{Dal, DalResult, Datastore, Error, Mysql, Pretrack, PretrackSourcePlatform, QueueEntry, QueueStatus};
 
 pub struct Track<T: Datastore> {
     dal: T,
@@ -22,6 +22,12 @@ pub struct TrackBuildable<T: Datastore> {
 
 pub trait TrackExt<T: Datastore> {
     fn get_queue_idx(&self) -> DalResult<i64>;
+    /// The User who enqueued the Track, if it is enqueued
+    fn get_added_by(&self) -> DalResult<Option<Uuid>>;
+    /// The state of the Track in the queue, if it is in the queue
+    fn get_queue_status(&self) -> DalResult<Option<QueueStatus>>;
+    /// The entry of the Track in the queue, if it is in the queue
+    fn get_queue_entry(&self) -> DalResult<Option<QueueEntry>>;
 }
 
 impl Dal<Mysql, TrackBuildable<Mysql>> for Track<Mysql> {
@@ -66,6 +72,14 @@ impl Dal<Mysql, TrackBuildable<Mysql>> for Track<Mysql> {
 
     fn create(dal: Mysql, buildable: TrackBuildable<Mysql>) -> DalResult<Self> {
         let mut conn = dal.get_conn()?;
+        Self::insert(&mut conn, dal, buildable)
+    }
+}
+
+impl Track<Mysql> {
+    /// Create a Track from a Pretrack on the given connection, which may be a transaction.
+    /// The Pretrack is deleted on the same connection
+    pub(crate) fn insert<Q: Queryable>(conn: &mut Q, dal: Mysql, buildable: TrackBuildable<Mysql>) -> DalResult<Self> {
         let uuid = Uuid::new_v4();
         conn.exec_drop("INSERT INTO tracks (uuid, room_uuid, name, artist, duration, thumbnail_url, platform, platform_video_id) VALUES (:uuid, :room_uuid, :name, :artist, :duration, :thumbnail_url, :platform, :platform_video_id)", params! {
             "uuid" => &uuid,
@@ -89,7 +103,9 @@ impl Dal<Mysql, TrackBuildable<Mysql>> for Track<Mysql> {
             platform: buildable.pretrack.platform.clone()
         };
 
-        buildable.pretrack.delete()?;
+        conn.exec_drop("DELETE FROM pretracks WHERE uuid = :uuid", params! {
+            "uuid" => &buildable.pretrack.uuid
+        })?;
 
         Ok(this)
     }
@@ -108,4 +124,52 @@ impl TrackExt<Mysql> for Track<Mysql> {
         let pos: i64 = row.get("idx").unwrap();
         Ok(pos)
     }
+
+    fn get_added_by(&self) -> DalResult<Option<Uuid>> {
+        let mut conn = self.dal.get_conn()?;
+        let row: Row = match conn.exec_first("SELECT added_by FROM queue WHERE track_uuid = :track_uuid", params! {
+            "track_uuid" => &self.uuid
+        })? {
+            Some(x) => x,
+            None => return Ok(None)
+        };
+
+        Ok(Some(row.get("added_by").unwrap()))
+    }
+
+    fn get_queue_status(&self) -> DalResult<Option<QueueStatus>> {
+        let mut conn = self.dal.get_conn()?;
+        let row: Row = match conn.exec_first("SELECT status FROM queue WHERE track_uuid = :track_uuid", params! {
+            "track_uuid" => &self.uuid
+        })? {
+            Some(x) => x,
+            None => return Ok(None)
+        };
+
+        let status: String = row.get("status").unwrap();
+        let status = QueueStatus::new(&status)
+            .ok_or(Error::Other(format!("Unknown queue status '{status}'")))?;
+        Ok(Some(status))
+    }
+
+    fn get_queue_entry(&self) -> DalResult<Option<QueueEntry>> {
+        let mut conn = self.dal.get_conn()?;
+        let row: Row = match conn.exec_first("SELECT idx,added_by,added_at,status FROM queue WHERE track_uuid = :track_uuid", params! {
+            "track_uuid" => &self.uuid
+        })? {
+            Some(x) => x,
+            None => return Ok(None)
+        };
+
+        let status: String = row.get("status").unwrap();
+        let status = QueueStatus::new(&status)
+            .ok_or(Error::Other(format!("Unknown queue status '{status}'")))?;
+
+        Ok(Some(QueueEntry {
+            idx: row.get("idx").unwrap(),
+            added_by: row.get("added_by").unwrap(),
+            added_at: row.get("added_at").unwrap(),
+            status,
+        }))
+    }
 }
\ No newline at end of file
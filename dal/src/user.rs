@@ -1,12 +1,59 @@
 use mysql::prelude::Queryable;
 use mysql::{params, TxOpts};
 use mysql_common::row::Row;
-use crate::{uuid::Uuid, Dal, DalResult, Datastore, Mysql};
+use rand::Rng;
+use crate::{uuid::Uuid, constant_time_eq, Dal, DalResult, Datastore, Mysql};
+
+/// The User tracks are added by when they are not added by a member of the Room, e.g. by autoplay.
+/// No row exists for this User
+pub const SYSTEM_USER_UUID: Uuid = Uuid::nil();
+
+const SECRET_LENGTH: usize = 32;
 
 pub struct User<T: Datastore> {
     dal: T,
     pub uuid: Uuid,
     pub name: String,
+    /// Proves the identity of the User when rejoining a Room.
+    /// Users created before secrets were introduced have none
+    pub secret: Option<String>,
+}
+
+impl<T: Datastore> User<T> {
+    /// Check the provided secret against the User's secret in constant time
+    pub fn verify_secret(&self, secret: &str) -> bool {
+        match &self.secret {
+            Some(x) => constant_time_eq(x, secret),
+            None => false
+        }
+    }
+}
+
+/// The role of a User in a Room
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemberRole {
+    Owner,
+    CoHost,
+    Member,
+}
+
+/// A Room a User is a member of
+pub struct UserRoom {
+    pub room_uuid: Uuid,
+    pub name: String,
+    pub role: MemberRole,
+    pub joined_at: i64,
+    /// All members of the Room, whether they are connected or not
+    pub member_count: u32,
+}
+
+pub trait UserExt<T: Datastore, U>: Dal<T, U> {
+    /// List the Rooms the User is a member of, in order of joining
+    fn list_rooms(&self) -> DalResult<Vec<UserRoom>>;
+}
+
+fn generate_secret() -> String {
+    rand::thread_rng().sample_iter(rand::distributions::Alphanumeric).take(SECRET_LENGTH).map(char::from).collect()
 }
 
 pub struct UserBuildable {
@@ -16,7 +63,7 @@ pub struct UserBuildable {
 impl Dal<Mysql, UserBuildable> for User<Mysql> {
     fn get(dal: Mysql, uuid: Uuid) -> DalResult<Option<Self>> {
         let mut tx = dal.start_transaction(TxOpts::default())?;
-        let row: Row = match tx.exec_first("SELECT name FROM users WHERE uuid = :uuid", params! {
+        let row: Row = match tx.exec_first("SELECT name,secret FROM users WHERE uuid = :uuid", params! {
             "uuid" => &uuid
         })? {
             Some(x) => x,
@@ -28,6 +75,7 @@ impl Dal<Mysql, UserBuildable> for User<Mysql> {
             dal,
             uuid,
             name: row.get("name").unwrap(),
+            secret: row.get("secret").unwrap(),
         }))
     }
 
@@ -54,16 +102,52 @@ impl Dal<Mysql, UserBuildable> for User<Mysql> {
     fn create(dal: Mysql, buildable: UserBuildable) -> DalResult<Self> {
         let mut tx = dal.start_transaction(TxOpts::default())?;
         let uuid = Uuid::new_v4();
+        let secret = generate_secret();
 
-        tx.exec_drop("INSERT INTO users (uuid, name) VALUES (:uuid, :name)", params! {
+        tx.exec_drop("INSERT INTO users (uuid, name, secret) VALUES (:uuid, :name, :secret)", params! {
             "uuid" => &uuid,
-            "name" => &buildable.name
+            "name" => &buildable.name,
+            "secret" => &secret
         })?;
         tx.commit()?;
         Ok(Self {
             dal,
             uuid,
-            name: buildable.name
+            name: buildable.name,
+            secret: Some(secret),
         })
     }
+}
+
+impl UserExt<Mysql, UserBuildable> for User<Mysql> {
+    fn list_rooms(&self) -> DalResult<Vec<UserRoom>> {
+        let mut conn = self.dal.get_conn()?;
+        let rows: Vec<Row> = conn.exec("SELECT rooms.uuid,rooms.name,rooms.owner,room_members.joined_at,room_members.co_host,(SELECT COUNT(*) FROM room_members AS members WHERE members.room_uuid = rooms.uuid) AS member_count FROM room_members JOIN rooms ON rooms.uuid = room_members.room_uuid WHERE room_members.user_uuid = :user_uuid ORDER BY room_members.joined_at", params! {
+            "user_uuid" => &self.uuid
+        })?;
+
+        let rooms = rows.into_iter()
+            .map(|row| {
+                let owner: Uuid = row.get("owner").unwrap();
+                let co_host: bool = row.get("co_host").unwrap();
+                let role = if owner.eq(&self.uuid) {
+                    MemberRole::Owner
+                } else if co_host {
+                    MemberRole::CoHost
+                } else {
+                    MemberRole::Member
+                };
+
+                UserRoom {
+                    room_uuid: row.get("uuid").unwrap(),
+                    name: row.get("name").unwrap(),
+                    role,
+                    joined_at: row.get("joined_at").unwrap(),
+                    member_count: row.get("member_count").unwrap(),
+                }
+            })
+            .collect();
+
+        Ok(rooms)
+    }
 }
\ No newline at end of file
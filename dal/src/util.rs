@@ -0,0 +1,28 @@
+use sha2::{Digest, Sha256};
+
+/// Compare two secrets without leaking how much of them matches, or their length, through timing.
+/// Both are hashed first, so the comparison always covers the same amount of bytes
+pub fn constant_time_eq(a: &str, b: &str) -> bool {
+    let a = Sha256::digest(a.as_bytes());
+    let b = Sha256::digest(b.as_bytes());
+    a.iter()
+        .zip(b.iter())
+        .fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn equal() {
+        assert!(constant_time_eq("secret", "secret"));
+    }
+
+    #[test]
+    fn different() {
+        assert!(!constant_time_eq("secret", "secreT"));
+        assert!(!constant_time_eq("secret", "secret2"));
+        assert!(!constant_time_eq("", "secret"));
+    }
+}
\ No newline at end of file